@@ -0,0 +1,135 @@
+//! Derive macros for `diesel-pg-type-utils`. Use them through the re-exports in that crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Path, Type};
+
+/// The derive equivalent of `wrap_type!`, for a tuple struct with a single field.
+///
+/// ```ignore
+/// #[derive(Debug, Copy, Clone, AsExpression, FromSqlRow, PgWrap)]
+/// #[diesel(sql_type = Numeric)]
+/// #[pg_wrap(sql = Numeric, via = PgU64)]
+/// pub struct AssetId(u64);
+/// ```
+///
+/// - `sql` is the diesel SQL type, and is required.
+/// - `via` is the type diesel decodes into first. Defaults to the field's type.
+/// - `from` is a `fn(&Via) -> Result<Field, E>` where `E: Display`. Failures come back as a
+///   `ConversionError` holding the decoded value, which has to be `Debug`. Defaults to
+///   `Into::into`.
+/// - `to` is a `fn(&Field) -> Via`. Defaults to `Into::into` on a clone of the field.
+/// - `db` is the backend. Defaults to `Pg`.
+///
+/// This only generates the `FromSql`/`ToSql`/`From`/`assume_valid`/`inner` items. The diesel
+/// (and serde) derives are left to the struct, so any other attribute can sit alongside them.
+#[proc_macro_derive(PgWrap, attributes(pg_wrap))]
+pub fn derive_pg_wrap(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_pg_wrap(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct PgWrapArgs {
+    sql: Option<Type>,
+    via: Option<Type>,
+    from: Option<Path>,
+    to: Option<Path>,
+    db: Option<Type>,
+}
+
+impl PgWrapArgs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("pg_wrap")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("sql") {
+                    args.sql = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("via") {
+                    args.via = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("from") {
+                    args.from = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("to") {
+                    args.to = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("db") {
+                    args.db = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected one of `sql`, `via`, `from`, `to` or `db`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(args)
+    }
+}
+
+fn expand_pg_wrap(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let args = PgWrapArgs::parse(&input)?;
+    let wrapped = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => fields.unnamed[0].ty.clone(),
+            fields => {
+                return Err(syn::Error::new(fields.span(), "PgWrap needs a tuple struct with exactly one field"));
+            },
+        },
+        _ => return Err(syn::Error::new(input.ident.span(), "PgWrap can only be derived for structs")),
+    };
+    let Some(sql) = args.sql else {
+        return Err(syn::Error::new(input.ident.span(), "PgWrap needs `#[pg_wrap(sql = ...)]`"));
+    };
+
+    let krate = quote!(::diesel_pg_type_utils);
+    let diesel = quote!(#krate::diesel);
+    let via = args.via.unwrap_or_else(|| wrapped.clone());
+    let db = args.db.map_or_else(|| quote!(#diesel::pg::Pg), |db| quote!(#db));
+    let convert_from = match args.from {
+        Some(from) => quote! {
+            #from(&intermediate).map_err(|e| #krate::error::ConversionError::invalid::<Self, #sql>(e, &intermediate))?
+        },
+        None => quote!(::core::convert::Into::into(intermediate)),
+    };
+    let convert_to = match args.to {
+        Some(to) => quote!(#to(&self.0)),
+        None => quote!(::core::convert::Into::into(::core::clone::Clone::clone(&self.0))),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #diesel::deserialize::FromSql<#sql, #db> for #name #ty_generics #where_clause {
+            fn from_sql(bytes: <#db as #diesel::backend::Backend>::RawValue<'_>) -> #diesel::deserialize::Result<Self> {
+                let intermediate = <#via as #diesel::deserialize::FromSql<#sql, #db>>::from_sql(bytes)
+                    .map_err(#krate::error::ConversionError::retarget::<Self>)?;
+                let converted: #wrapped = #convert_from;
+                Ok(Self(converted))
+            }
+        }
+
+        impl #impl_generics #diesel::serialize::ToSql<#sql, #db> for #name #ty_generics #where_clause {
+            fn to_sql<'b>(&'b self, out: &mut #diesel::serialize::Output<'b, '_, #db>) -> #diesel::serialize::Result {
+                let intermediate: #via = #convert_to;
+                <#via as #diesel::serialize::ToSql<#sql, #db>>::to_sql(&intermediate, &mut out.reborrow())
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for #wrapped #where_clause {
+            fn from(f: #name #ty_generics) -> Self {
+                f.0
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn assume_valid(f: #wrapped) -> Self {
+                Self(f)
+            }
+
+            pub fn inner(&self) -> &#wrapped {
+                &self.0
+            }
+        }
+    })
+}
@@ -1,6 +1,7 @@
 use diesel::pg::Pg;
 
 use diesel_pg_type_utils::wrap::{
+    id_subtype,
     wrap_i64,
     wrap_i32,
     wrap_u64,
@@ -28,12 +29,8 @@ wrap_i32!(WeeklyExpeditionId<DB>);
 
 wrap_i32!(ProtagonistId<DB>);
 wrap_u64!(ProtagonistAssetId<DB>);
-impl ProtagonistAssetId {
-    /// Note: the inverse of this is technically not always correct, so is omitted.
-    pub fn as_asset_id(&self) -> AssetId {
-        AssetId(self.0)
-    }
-}
+// Not every asset is a protagonist, so there is no downcast.
+id_subtype!(ProtagonistAssetId: AssetId);
 
 wrap_i32!(LevelingCostId<DB>);
 
@@ -54,12 +51,8 @@ wrap_i32!(BotItemListingId<DB>);
 
 wrap_i32!(ItemId<DB>);
 wrap_u64!(CardId<DB>);
-impl CardId {
-    /// Note: the inverse of this is technically not always correct, so is omitted.
-    pub fn as_asset_id(&self) -> AssetId {
-        AssetId(self.0)
-    }
-}
+// Not every asset is a card, so there is no downcast.
+id_subtype!(CardId: AssetId);
 wrap_i32!(BotItemId<DB>);
 wrap_i32!(ConsumableArchetypeId<DB>);
 wrap_i32!(CollectibleArchetypeId<DB>);
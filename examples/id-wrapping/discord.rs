@@ -1,121 +1,19 @@
-pub use serenity::model::id::UserId as InternalDiscordUserId;
-pub use serenity::model::id::MessageId as InternalDiscordMessageId;
-pub use serenity::model::id::InteractionId as InternalDiscordInteractionId;
-pub use serenity::model::id::ChannelId as InternalDiscordChannelId;
-pub use serenity::model::id::GuildId as InternalDiscordGuildId;
-pub use serenity::model::id::RoleId as InternalDiscordRoleId;
+// `DiscordUserId`, `DiscordGuildId`, `DiscordChannelId`, `DiscordMessageId`, `DiscordRoleId` and
+// `DiscordInteractionId` come from `diesel_pg_type_utils::discord`, with the `serenity` feature.
+// Any other serenity ID can be wrapped the same way.
+use serenity::model::id::EmojiId as InternalDiscordEmojiId;
 
-use diesel::{
-    pg::Pg,
-    sql_types::Numeric,
-};
+use diesel::pg::Pg;
 
-use diesel_pg_type_utils::wrap_i32;
-use diesel_pg_type_utils::{
-    wrap::{
-        wrap_type,
-        wrap_i64,
-    },
-    PgU64,
+use diesel_pg_type_utils::wrap::{
+    wrap_snowflake,
+    wrap_i32,
+    wrap_i64,
 };
 
 type DB = Pg;
 
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordUserId<DB>(Numeric > PgU64 > InternalDiscordUserId)
-        |pgu| {
-            InternalDiscordUserId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordUserId> for DiscordUserId {
-    fn from(user_id: InternalDiscordUserId) -> Self {
-        Self(user_id)
-    }
-}
-
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordMessageId<DB>(Numeric > PgU64 > InternalDiscordMessageId)
-        |pgu| {
-            InternalDiscordMessageId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordMessageId> for DiscordMessageId {
-    fn from(message_id: InternalDiscordMessageId) -> Self {
-        Self(message_id)
-    }
-}
-
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordInteractionId<DB>(Numeric > PgU64 > InternalDiscordInteractionId)
-        |pgu| {
-            InternalDiscordInteractionId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordInteractionId> for DiscordInteractionId {
-    fn from(interaction_id: InternalDiscordInteractionId) -> Self {
-        Self(interaction_id)
-    }
-}
-
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordChannelId<DB>(Numeric > PgU64 > InternalDiscordChannelId)
-        |pgu| {
-            InternalDiscordChannelId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordChannelId> for DiscordChannelId {
-    fn from(channel_id: InternalDiscordChannelId) -> Self {
-        Self(channel_id)
-    }
-}
-
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordGuildId<DB>(Numeric > PgU64 > InternalDiscordGuildId)
-        |pgu| {
-            InternalDiscordGuildId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordGuildId> for DiscordGuildId {
-    fn from(guild_id: InternalDiscordGuildId) -> Self {
-        Self(guild_id)
-    }
-}
-
-wrap_type! {
-    #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    DiscordRoleId<DB>(Numeric > PgU64 > InternalDiscordRoleId)
-        |pgu| {
-            InternalDiscordRoleId(*pgu.inner())
-        }
-        |u| {
-            &PgU64::from(u.0)
-        }
-}
-impl From<InternalDiscordRoleId> for DiscordRoleId {
-    fn from(guild_id: InternalDiscordRoleId) -> Self {
-        Self(guild_id)
-    }
-}
+wrap_snowflake!(DiscordEmojiId<DB>(bigint > InternalDiscordEmojiId) with(from));
 
 wrap_i64!(UserJourneyId<DB>);
 wrap_i64!(UserJourneyUIId<DB>);
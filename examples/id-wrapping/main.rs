@@ -1,6 +1,9 @@
 mod arcadia;
 mod discord;
 mod shared;
+// Only declarations, which nothing uses.
+#[allow(dead_code)]
+mod tables;
 
 /// Ignored values
 fn main() {}
@@ -0,0 +1,25 @@
+//! The same kind of IDs as `arcadia`, but with one generic type scoped by diesel's tables.
+
+use diesel_pg_type_utils::Id;
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel_pg_type_utils::id::IdType;
+
+    users (id) {
+        id -> IdType<crate::tables::users::table, Integer>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel_pg_type_utils::id::IdType;
+
+    training_sessions (id) {
+        id -> IdType<crate::tables::training_sessions::table>,
+        user_id -> IdType<crate::tables::users::table, Integer>,
+    }
+}
+
+pub type UserId = Id<users::table, i32>;
+pub type TrainingSessionId = Id<training_sessions::table>;
@@ -0,0 +1,51 @@
+//! Serenity's Discord IDs, stored as `numeric` through [`PgU64`](crate::PgU64). Other IDs can be
+//! wrapped the same way with [`wrap_snowflake!`](crate::wrap::wrap_snowflake).
+
+use diesel::pg::Pg;
+use serenity::model::id::{ChannelId, GuildId, InteractionId, MessageId, RoleId, UserId};
+
+use crate::wrap::wrap_snowflake;
+
+wrap_snowflake!(DiscordUserId<Pg>(numeric > UserId) with(from));
+wrap_snowflake!(DiscordGuildId<Pg>(numeric > GuildId) with(from));
+wrap_snowflake!(DiscordChannelId<Pg>(numeric > ChannelId) with(from));
+wrap_snowflake!(DiscordMessageId<Pg>(numeric > MessageId) with(from));
+wrap_snowflake!(DiscordRoleId<Pg>(numeric > RoleId) with(from));
+wrap_snowflake!(DiscordInteractionId<Pg>(numeric > InteractionId) with(from));
+
+#[cfg(test)]
+mod test {
+    use diesel::{debug_query, pg::Pg, prelude::*};
+    use serenity::model::id::{EmojiId, UserId};
+
+    use crate::{snowflake::Snowflake, wrap::wrap_snowflake, PgU64};
+    use super::DiscordUserId;
+
+    wrap_snowflake!(pub(super) EmojiKey<Pg>(bigint > EmojiId));
+
+    diesel::table! {
+        reactions (id) {
+            id -> BigInt,
+            user_id -> Numeric,
+            emoji_id -> BigInt,
+        }
+    }
+
+    #[test]
+    fn snowflake() {
+        let user = DiscordUserId::from(UserId(80351110224678912));
+        assert_eq!(UserId::from(user), UserId(80351110224678912));
+        assert_eq!(*user.inner(), UserId(80351110224678912));
+        assert_eq!(user.created_at(), PgU64::from(80351110224678912).created_at());
+        assert_eq!(*EmojiKey::assume_valid(EmojiId(5)).inner(), EmojiId(5));
+
+        let query = reactions::table
+            .filter(reactions::user_id.eq(user))
+            .filter(reactions::emoji_id.eq(EmojiKey::assume_valid(EmojiId(5))))
+            .select(reactions::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "reactions"."id" FROM "reactions" WHERE (("reactions"."user_id" = $1) AND ("reactions"."emoji_id" = $2)) -- binds: [DiscordUserId(UserId(80351110224678912)), EmojiKey(EmojiId(5))]"#,
+        );
+    }
+}
@@ -0,0 +1,803 @@
+//! Enums stored as plain Postgres columns.
+
+use diesel::{
+    expression::{AppearsOnTable, Expression, SelectableExpression, ValidGrouping},
+    pg::Pg,
+    query_builder::{AstPass, QueryFragment, QueryId},
+    sql_types::{BigInt, Text},
+    QueryResult,
+};
+
+/// How the `FromStr` impl of a `text_enum!` matches its input against the variants' forms.
+///
+/// An exact match always wins. With `prefix`, an input that only starts with a form matches the
+/// longest such form, so `"unequipall"` isn't read as `"unequip"`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ParseMode {
+    pub case_insensitive: bool,
+    pub prefix: bool,
+}
+
+impl ParseMode {
+    pub const EXACT: Self = Self {
+        case_insensitive: false,
+        prefix: false,
+    };
+
+    pub const fn case_insensitive(self) -> Self {
+        Self { case_insensitive: true, ..self }
+    }
+
+    pub const fn prefix(self) -> Self {
+        Self { prefix: true, ..self }
+    }
+
+    /// Case-insensitive matching only folds ASCII letters, so nothing is allocated.
+    pub fn parse<T: Copy>(self, s: &str, forms: &[(&str, T)]) -> Option<T> {
+        let matches = |input: &str, form: &str| if self.case_insensitive {
+            input.eq_ignore_ascii_case(form)
+        } else {
+            input == form
+        };
+        if let Some((_, value)) = forms.iter().find(|(form, _)| matches(s, form)) {
+            return Some(*value);
+        }
+        if !self.prefix {
+            return None;
+        }
+        // `max_by_key` keeps the last of equal forms, so reverse to prefer the first declared.
+        forms
+            .iter()
+            .rev()
+            .filter(|(form, _)| s.get(..form.len()).is_some_and(|start| matches(start, form)))
+            .max_by_key(|(form, _)| form.len())
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Declares a fieldless enum stored as `TEXT`, with each variant's canonical string.
+///
+/// ```ignore
+/// text_enum! {
+///     #[derive(PartialOrd, Ord)]
+///     pub enum ShopLimitBoundary<Pg> {
+///         Daily = "daily",
+///         Weekly = "weekly",
+///         Never = "never",
+///     }
+/// }
+/// ```
+///
+/// This generates `as_str`, `ALL` and `VARIANTS` (in declaration order), `Display`, `FromStr`,
+/// serde impls that use the strings, and the diesel `Text` conversions. Unknown strings fail with
+/// a [`ParseEnumError`](crate::error::ParseEnumError) from `FromStr`, and with a
+/// [`ConversionError`](crate::error::ConversionError) holding the string when decoding.
+///
+/// A variant can list aliases after its canonical string, as in `North = "north" | "n" | "1"`,
+/// and `parse(case_insensitive, prefix)` after the name picks a [`ParseMode`]. Aliases and the
+/// parse mode apply to everything that parses, including decoding, but only the canonical string
+/// is ever displayed, serialized or written to the database. `str_forms()` lists every accepted
+/// string, aliases included.
+///
+/// `ordered` after the name (and parse mode) implements `Ord` by declaration order, or by rank for
+/// variants declared like `Bonus = "bonus" rank 10`. It also adds `rank`, `min`, `max`, `next`,
+/// `prev`, and `rank_case` to sort the same way in SQL. Ranks on an enum that isn't `ordered`
+/// are an error:
+///
+/// ```compile_fail
+/// use diesel::pg::Pg;
+///
+/// diesel_pg_type_utils::enums::text_enum! {
+///     pub enum Reward<Pg> {
+///         Bonus = "bonus" rank 10,
+///     }
+/// }
+/// ```
+///
+/// Constant metadata can be attached to each variant by naming a struct and its fields last, as
+/// in `enum Kind<Pg> meta KindMeta { atomic: bool, category: &'static str } { ... }`, and then
+/// giving every variant its values, as in `Use = "use" { atomic: false, category: "item" }`. This
+/// declares the struct and adds `meta`, a `const fn` accessor per field (so the field types have
+/// to be `Copy`), and `filter_meta` to find the variants with some metadata. Values without a
+/// `meta` declaration are an error rather than being dropped:
+///
+/// ```compile_fail
+/// use diesel::pg::Pg;
+///
+/// diesel_pg_type_utils::enums::text_enum! {
+///     pub enum Kind<Pg> {
+///         Use = "use" { atomic: false },
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! text_enum {
+    {
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident <$db:ty> $(parse($($mode:ident),* $(,)?))? ordered $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        $crate::enums::text_enum! {
+            @public [ordered] $(#[$attr])* $vis enum $name <$db> $(parse($($mode),*))? $(meta $meta { $($field: $field_ty),* })? {
+                $($body)*
+            }
+        }
+    };
+    {
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident <$db:ty> $(parse($($mode:ident),* $(,)?))? $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        $crate::enums::text_enum! {
+            @public [] $(#[$attr])* $vis enum $name <$db> $(parse($($mode),*))? $(meta $meta { $($field: $field_ty),* })? {
+                $($body)*
+            }
+        }
+    };
+    {
+        @public [$($ordered:ident)?]
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident <$db:ty> $(parse($($mode:ident),*))? $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        $crate::enums::text_enum! {
+            @common [$(#[$attr])*] [$vis] $name, $crate::diesel::sql_types::Text, [$($($mode),*)?] [$($ordered)?] [$(meta $meta { $($field: $field_ty),* })?] {
+                $($body)*
+            }
+        }
+
+        impl $crate::diesel::deserialize::FromSql<$crate::diesel::sql_types::Text, $db> for $name {
+            fn from_sql(bytes: <$db as $crate::diesel::backend::Backend>::RawValue<'_>) -> $crate::diesel::deserialize::Result<Self> {
+                let s = ::core::str::from_utf8(bytes.as_bytes())?;
+                s.parse().map_err(|_| {
+                    $crate::error::ConversionError::new::<Self, $crate::diesel::sql_types::Text>($crate::error::EnumError::Unknown, s).into()
+                })
+            }
+        }
+
+        impl $crate::diesel::serialize::ToSql<$crate::diesel::sql_types::Text, $db> for $name {
+            fn to_sql<'b>(&'b self, out: &mut $crate::diesel::serialize::Output<'b, '_, $db>) -> $crate::diesel::serialize::Result {
+                <str as $crate::diesel::serialize::ToSql<$crate::diesel::sql_types::Text, $db>>::to_sql(
+                    self.as_str(),
+                    &mut out.reborrow()
+                )
+            }
+        }
+    };
+    // Everything but the diesel conversions, which `pg_enum!` shares.
+    {
+        @common [$($attr:tt)*] [$($vis:tt)*] $name:ident, $sql:ty, [$($mode:ident),*] [$($ordered:ident)?]
+        [$(meta $meta:ident { $($field:ident : $field_ty:ty),* })?] {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $text:literal $(| $alias:literal)* $(rank $rank:literal)?
+                $({ $($variant_field:ident : $value:expr),* $(,)? })?
+            ),+ $(,)?
+        }
+    } => {
+        $crate::enums::text_enum!(@ordered [$($ordered)?] $name, $sql { $($variant = $text $(| $alias)* $(rank $rank)?),+ });
+        $crate::enums::text_enum! {
+            @meta [$(meta $meta { $($field: $field_ty),* })?] [$($vis)*] $name {
+                $($variant $({ $($variant_field: $value),* })?),+
+            }
+        }
+
+        $($attr)*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[derive($crate::diesel::AsExpression, $crate::diesel::FromSqlRow)]
+        #[diesel(sql_type = $sql)]
+        $($vis)* enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )+
+        }
+
+        impl $name {
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+            pub const VARIANTS: &'static [&'static str] = &[$($text),+];
+
+            pub const PARSE_MODE: $crate::enums::ParseMode = $crate::enums::ParseMode::EXACT $(.$mode())*;
+
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $text,)+
+                }
+            }
+
+            pub const fn str_forms() -> &'static [&'static str] {
+                &[$($text, $($alias,)*)+]
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.pad(self.as_str())
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::error::ParseEnumError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                const FORMS: &[(&str, $name)] = &[$(($text, $name::$variant), $(($alias, $name::$variant),)*)+];
+                let parsed = if Self::PARSE_MODE == $crate::enums::ParseMode::EXACT {
+                    match s {
+                        $($text $(| $alias)* => Some(Self::$variant),)+
+                        _ => None,
+                    }
+                } else {
+                    Self::PARSE_MODE.parse(s, FORMS)
+                };
+                parsed.ok_or_else(|| $crate::error::ParseEnumError::new::<Self>(s))
+            }
+        }
+
+        impl $crate::serde::Serialize for $name {
+            fn serialize<S: $crate::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = <String as $crate::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                s.parse().map_err(<D::Error as $crate::serde::de::Error>::custom)
+            }
+        }
+    };
+    (@meta [] [$($vis:tt)*] $name:ident { $($variant:ident),+ }) => {};
+    (@meta [] $($rest:tt)*) => {
+        ::core::compile_error!("variant metadata needs a `meta Struct { field: Type, ... }` declaration before the variants");
+    };
+    {
+        @meta [meta $meta:ident { $($field:ident : $field_ty:ty),* }] [$($vis:tt)*] $name:ident {
+            $($variant:ident $({ $($variant_field:ident : $value:expr),* })?),+
+        }
+    } => {
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        $($vis)* struct $meta {
+            $(pub $field: $field_ty,)*
+        }
+
+        impl $name {
+            pub const fn meta(&self) -> &'static $meta {
+                match self {
+                    $(Self::$variant => {
+                        const META: $meta = $meta { $($($variant_field: $value),*)? };
+                        &META
+                    })+
+                }
+            }
+
+            $(
+                pub const fn $field(&self) -> $field_ty {
+                    self.meta().$field
+                }
+            )*
+
+            /// The variants whose metadata matches `filter`, in declaration order.
+            pub fn filter_meta(filter: impl Fn(&$meta) -> bool) -> impl Iterator<Item = Self> {
+                Self::ALL.iter().copied().filter(move |variant| filter(variant.meta()))
+            }
+        }
+    };
+    (@ordered [] $name:ident, $sql:ty { $($variant:ident = $text:literal $(| $alias:literal)*),+ }) => {};
+    (@ordered [] $($rest:tt)*) => {
+        ::core::compile_error!("variant ranks need `ordered` after the enum name");
+    };
+    (@ordered [ordered] $name:ident, $sql:ty { $($variant:ident = $text:literal $(| $alias:literal)* $(rank $rank:literal)?),+ }) => {
+        impl $name {
+            /// The explicit rank, or the declaration index for variants without one.
+            pub const fn rank(&self) -> i64 {
+                match self {
+                    $(Self::$variant => $crate::enums::rank_or_index(&[$($rank)?], Self::$variant as i64),)+
+                }
+            }
+
+            pub fn min() -> Self {
+                *Self::ALL.iter().min().expect("enums have at least one variant")
+            }
+
+            pub fn max() -> Self {
+                *Self::ALL.iter().max().expect("enums have at least one variant")
+            }
+
+            /// The next variant up, or `None` for the last one.
+            pub fn next(&self) -> Option<Self> {
+                Self::ALL.iter().filter(|variant| *variant > self).min().copied()
+            }
+
+            /// The next variant down, or `None` for the first one.
+            pub fn prev(&self) -> Option<Self> {
+                Self::ALL.iter().filter(|variant| *variant < self).max().copied()
+            }
+
+            /// A `CASE` expression mapping `column` to each variant's rank, for `ORDER BY` or
+            /// comparisons in SQL that agree with `Ord`, as described on `enums::RankCase`.
+            pub fn rank_case<C>(column: C) -> $crate::enums::RankCase<C>
+            where
+                C: $crate::diesel::Column + $crate::diesel::Expression<SqlType = $sql>,
+            {
+                const RANKS: &[(&str, i64)] = &[$(($text, $name::$variant.rank()), $(($alias, $name::$variant.rank()),)*)+];
+                $crate::enums::RankCase::new(column, RANKS, Self::PARSE_MODE.case_insensitive)
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                // Break ties between equal ranks by declaration order, to stay consistent with `Eq`.
+                (self.rank(), *self as i64).cmp(&(other.rank(), *other as i64))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn rank_or_index(rank: &[i64], index: i64) -> i64 {
+    match rank {
+        [rank] => *rank,
+        _ => index,
+    }
+}
+
+/// The `rank_case` of an `ordered` enum: `CASE CAST(column AS TEXT) WHEN ... THEN rank ... END`,
+/// with an arm for every accepted string, aliases included, bound as parameters. Case-insensitive
+/// enums compare lowercased strings. Anything else, like a string that only matches with the
+/// `prefix` parse mode, gets `i64::MAX` and sorts last.
+#[derive(Debug, Copy, Clone)]
+pub struct RankCase<C> {
+    column: C,
+    ranks: &'static [(&'static str, i64)],
+    case_insensitive: bool,
+}
+
+impl<C> RankCase<C> {
+    #[doc(hidden)]
+    pub fn new(column: C, ranks: &'static [(&'static str, i64)], case_insensitive: bool) -> Self {
+        Self { column, ranks, case_insensitive }
+    }
+
+    fn push_text<'b>(&self, out: &mut AstPass<'_, 'b, Pg>, push: impl FnOnce(&mut AstPass<'_, 'b, Pg>) -> QueryResult<()>) -> QueryResult<()> {
+        out.push_sql(if self.case_insensitive { "LOWER(" } else { "(" });
+        push(out)?;
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+impl<C: Expression> Expression for RankCase<C> {
+    type SqlType = BigInt;
+}
+
+impl<C: AppearsOnTable<QS>, QS: ?Sized> AppearsOnTable<QS> for RankCase<C> {}
+
+impl<C: SelectableExpression<QS>, QS: ?Sized> SelectableExpression<QS> for RankCase<C> {}
+
+impl<C: ValidGrouping<GB>, GB> ValidGrouping<GB> for RankCase<C> {
+    type IsAggregate = C::IsAggregate;
+}
+
+impl<C> QueryId for RankCase<C> {
+    type QueryId = ();
+
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<C: QueryFragment<Pg>> QueryFragment<Pg> for RankCase<C> {
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_sql("CASE ");
+        self.push_text(&mut out, |out| {
+            out.push_sql("CAST(");
+            self.column.walk_ast(out.reborrow())?;
+            out.push_sql(" AS TEXT)");
+            Ok(())
+        })?;
+        for (form, rank) in self.ranks {
+            out.push_sql(" WHEN ");
+            self.push_text(&mut out, |out| out.push_bind_param::<Text, str>(form))?;
+            out.push_sql(&format!(" THEN {rank}"));
+        }
+        out.push_sql(&format!(" ELSE {} END", i64::MAX));
+        Ok(())
+    }
+}
+
+/// Like `text_enum!`, but stored as a native Postgres enum type instead of `TEXT`.
+///
+/// ```ignore
+/// pg_enum! {
+///     pub enum ShopLimitBoundary(ShopLimitBoundaryType = "shop_limit_boundary") {
+///         Daily = "daily",
+///         Weekly = "weekly",
+///         Never = "never",
+///     }
+/// }
+/// ```
+///
+/// This also declares `ShopLimitBoundaryType`, the diesel `SqlType` for the Postgres type named
+/// `shop_limit_boundary`, to use in `table!` in place of the one `diesel print-schema` generates.
+/// `ShopLimitBoundary::create_type_sql()` returns the `CREATE TYPE` statement for a migration, with
+/// the canonical strings as the labels in declaration order.
+#[macro_export]
+macro_rules! pg_enum {
+    {
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident ( $sql:ident = $pg_name:literal ) $(parse($($mode:ident),* $(,)?))? ordered $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        $crate::enums::pg_enum! {
+            @public [ordered] $(#[$attr])* $vis enum $name ( $sql = $pg_name ) $(parse($($mode),*))? $(meta $meta { $($field: $field_ty),* })? {
+                $($body)*
+            }
+        }
+    };
+    {
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident ( $sql:ident = $pg_name:literal ) $(parse($($mode:ident),* $(,)?))? $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        $crate::enums::pg_enum! {
+            @public [] $(#[$attr])* $vis enum $name ( $sql = $pg_name ) $(parse($($mode),*))? $(meta $meta { $($field: $field_ty),* })? {
+                $($body)*
+            }
+        }
+    };
+    {
+        @public [$($ordered:ident)?]
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident ( $sql:ident = $pg_name:literal ) $(parse($($mode:ident),*))? $(meta $meta:ident { $($field:ident : $field_ty:ty),* $(,)? })? {
+            $($body:tt)*
+        }
+    } => {
+        #[derive(Debug, Copy, Clone, Default, $crate::diesel::sql_types::SqlType, $crate::diesel::query_builder::QueryId)]
+        #[diesel(postgres_type(name = $pg_name))]
+        $vis struct $sql;
+
+        $crate::enums::text_enum! {
+            @common [$(#[$attr])*] [$vis] $name, $sql, [$($($mode),*)?] [$($ordered)?] [$(meta $meta { $($field: $field_ty),* })?] {
+                $($body)*
+            }
+        }
+
+        impl $name {
+            pub const PG_TYPE_NAME: &'static str = $pg_name;
+
+            pub fn create_type_sql() -> String {
+                $crate::enums::create_type_sql($pg_name, Self::VARIANTS)
+            }
+        }
+
+        impl $crate::diesel::deserialize::FromSql<$sql, $crate::diesel::pg::Pg> for $name {
+            fn from_sql(bytes: $crate::diesel::pg::PgValue<'_>) -> $crate::diesel::deserialize::Result<Self> {
+                let s = ::core::str::from_utf8(bytes.as_bytes())?;
+                s.parse().map_err(|_| {
+                    $crate::error::ConversionError::new::<Self, $sql>($crate::error::EnumError::Unknown, s).into()
+                })
+            }
+        }
+
+        impl $crate::diesel::serialize::ToSql<$sql, $crate::diesel::pg::Pg> for $name {
+            fn to_sql<'b>(&'b self, out: &mut $crate::diesel::serialize::Output<'b, '_, $crate::diesel::pg::Pg>) -> $crate::diesel::serialize::Result {
+                ::std::io::Write::write_all(out, self.as_str().as_bytes())?;
+                Ok($crate::diesel::serialize::IsNull::No)
+            }
+        }
+    };
+}
+
+/// `CREATE TYPE name AS ENUM (...)` for the given labels. The name is used as is, so it can be
+/// schema qualified or quoted, while the labels are quoted as string literals.
+pub fn create_type_sql(name: &str, labels: &[&str]) -> String {
+    let labels = labels.iter().map(|label| format!("'{}'", label.replace('\'', "''"))).collect::<Vec<_>>();
+    format!("CREATE TYPE {name} AS ENUM ({})", labels.join(", "))
+}
+
+/// Declares a fieldless enum stored as an integer column, with each variant's code.
+///
+/// ```ignore
+/// int_enum! {
+///     pub enum UiKind<Pg>(Integer > i32) {
+///         Message = 0,
+///         Button = 1,
+///         Select = 2,
+///         _ => Unknown,
+///     }
+/// }
+/// ```
+///
+/// The SQL type and the Rust integer it decodes through go in the parentheses, so `SmallInt >
+/// i16` and `BigInt > i64` work the same way. This generates `code`, `ALL`, `From<Self>` for the
+/// integer, serde impls that use the code, and the diesel conversions.
+///
+/// The optional `_ => Unknown` adds an `Unknown` variant (named as given) holding the integer, that
+/// every other code decodes into and that writes its code back unchanged, so rows written by a newer
+/// deployment can still be read. With it the enum is `From` the integer, and without it
+/// `TryFrom`, failing with a [`ParseEnumError`](crate::error::ParseEnumError) or, when decoding,
+/// a [`ConversionError`](crate::error::ConversionError).
+#[macro_export]
+macro_rules! int_enum {
+    {
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident <$db:ty> ( $sql:ty > $repr:ty ) {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $code:literal
+            ),+
+            $(, _ => $unknown:ident)? $(,)?
+        }
+    } => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[derive($crate::diesel::AsExpression, $crate::diesel::FromSqlRow)]
+        #[diesel(sql_type = $sql)]
+        $vis enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )+
+            $($unknown($repr),)?
+        }
+
+        impl $name {
+            /// Every variant except the catch-all, in declaration order.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            pub const fn code(&self) -> $repr {
+                match self {
+                    $(Self::$variant => $code,)+
+                    $(Self::$unknown(code) => *code,)?
+                }
+            }
+        }
+
+        $crate::enums::int_enum!(@from_code $name $repr { $($variant = $code),+ } $($unknown)?);
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                value.code()
+            }
+        }
+
+        impl $crate::serde::Serialize for $name {
+            fn serialize<S: $crate::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                <$repr as $crate::serde::Serialize>::serialize(&self.code(), serializer)
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let code = <$repr as $crate::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                Self::try_from(code).map_err(<D::Error as $crate::serde::de::Error>::custom)
+            }
+        }
+
+        impl $crate::diesel::deserialize::FromSql<$sql, $db> for $name {
+            fn from_sql(bytes: <$db as $crate::diesel::backend::Backend>::RawValue<'_>) -> $crate::diesel::deserialize::Result<Self> {
+                let code = <$repr as $crate::diesel::deserialize::FromSql<$sql, $db>>::from_sql(bytes)?;
+                Self::try_from(code).map_err(|_| {
+                    $crate::error::ConversionError::new::<Self, $sql>($crate::error::EnumError::Unknown, code).into()
+                })
+            }
+        }
+
+        impl $crate::diesel::serialize::ToSql<$sql, $db> for $name {
+            fn to_sql<'b>(&'b self, out: &mut $crate::diesel::serialize::Output<'b, '_, $db>) -> $crate::diesel::serialize::Result {
+                let code = self.code();
+                <$repr as $crate::diesel::serialize::ToSql<$sql, $db>>::to_sql(&code, &mut out.reborrow())
+            }
+        }
+    };
+    (@from_code $name:ident $repr:ty { $($variant:ident = $code:literal),+ }) => {
+        impl TryFrom<$repr> for $name {
+            type Error = $crate::error::ParseEnumError;
+
+            fn try_from(code: $repr) -> Result<Self, Self::Error> {
+                match code {
+                    $($code => Ok(Self::$variant),)+
+                    _ => Err($crate::error::ParseEnumError::new::<Self>(code)),
+                }
+            }
+        }
+    };
+    (@from_code $name:ident $repr:ty { $($variant:ident = $code:literal),+ } $unknown:ident) => {
+        impl From<$repr> for $name {
+            fn from(code: $repr) -> Self {
+                match code {
+                    $($code => Self::$variant,)+
+                    _ => Self::$unknown(code),
+                }
+            }
+        }
+    };
+}
+
+pub use text_enum;
+pub use pg_enum;
+pub use int_enum;
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use diesel::{debug_query, pg::Pg, prelude::*, sql_types::{Integer, SmallInt, Text}};
+
+    use crate::{enums::{self, ParseMode}, error::{ConversionError, ConversionErrorKind, EnumError, ParseEnumError}};
+
+    enums::text_enum! {
+        /// Doc comments are kept.
+        pub enum Boundary<Pg> {
+            Daily = "daily",
+            Weekly = "weekly",
+            Never = "never",
+        }
+    }
+
+    enums::text_enum! {
+        pub enum Direction<Pg> parse(case_insensitive) {
+            North = "north" | "n" | "1",
+            South = "south" | "s" | "2",
+        }
+    }
+
+    enums::text_enum! {
+        pub enum Command<Pg> parse(prefix) {
+            Unequip = "unequip",
+            UnequipAll = "unequipall" | "ua",
+        }
+    }
+
+    #[test]
+    fn parse_modes() {
+        assert_eq!(Direction::PARSE_MODE, ParseMode::EXACT.case_insensitive());
+        assert_eq!("N".parse(), Ok(Direction::North));
+        assert_eq!("South".parse(), Ok(Direction::South));
+        assert!("no".parse::<Direction>().is_err());
+        assert_eq!(Direction::str_forms(), ["north", "n", "1", "south", "s", "2"]);
+        assert_eq!(Direction::South.to_string(), "south");
+
+        assert_eq!("unequip sword".parse(), Ok(Command::Unequip));
+        assert_eq!("unequipall".parse(), Ok(Command::UnequipAll));
+        assert_eq!("ua now".parse(), Ok(Command::UnequipAll));
+        assert!("UNEQUIP".parse::<Command>().is_err());
+        assert_eq!(ParseMode::EXACT.case_insensitive().prefix().parse("NÄ", &[("n", 1), ("nä", 2)]), Some(1));
+        assert_eq!(ParseMode::EXACT.prefix().parse("ä", &[("\u{c3}", 1)]), None);
+    }
+
+    enums::int_enum! {
+        pub enum UiKind<Pg>(Integer > i32) {
+            Message = 0,
+            Button = 1,
+            Select = -1,
+        }
+    }
+
+    enums::int_enum! {
+        pub enum Rarity<Pg>(SmallInt > i16) {
+            Common = 1,
+            Rare = 2,
+            _ => Unknown,
+        }
+    }
+
+    #[test]
+    fn int_enum() {
+        assert_eq!(UiKind::ALL, [UiKind::Message, UiKind::Button, UiKind::Select]);
+        assert_eq!(UiKind::try_from(-1), Ok(UiKind::Select));
+        assert_eq!(i32::from(UiKind::Button), 1);
+        assert_eq!(UiKind::try_from(3).unwrap_err().value, "3");
+
+        assert_eq!(Rarity::ALL, [Rarity::Common, Rarity::Rare]);
+        assert_eq!(Rarity::from(2), Rarity::Rare);
+        assert_eq!(Rarity::from(7), Rarity::Unknown(7));
+        assert_eq!(i16::from(Rarity::Unknown(7)), 7);
+    }
+
+    enums::pg_enum! {
+        pub enum Mood(MoodType = "mood") parse(case_insensitive) ordered {
+            Happy = "happy",
+            Wary = "o'wary",
+        }
+    }
+
+    #[test]
+    fn pg_enum() {
+        assert_eq!(Mood::PG_TYPE_NAME, "mood");
+        assert_eq!(Mood::create_type_sql(), "CREATE TYPE mood AS ENUM ('happy', 'o''wary')");
+        assert_eq!("o'wary".parse(), Ok(Mood::Wary));
+    }
+
+    enums::text_enum! {
+        pub enum Tier<Pg> ordered {
+            Bad = "bad",
+            Okay = "okay" | "ok",
+            Bonus = "bonus" rank 5,
+            Good = "good" rank 3,
+        }
+    }
+
+    diesel::table! {
+        use diesel::sql_types::*;
+        use super::MoodType;
+
+        rewards (id) {
+            id -> BigInt,
+            tier -> Text,
+            mood -> MoodType,
+        }
+    }
+
+    #[test]
+    fn ordered() {
+        assert!(Tier::Bad < Tier::Okay && Tier::Okay < Tier::Good && Tier::Good < Tier::Bonus);
+        assert_eq!(Tier::min(), Tier::Bad);
+        assert_eq!(Tier::max(), Tier::Bonus);
+        assert_eq!(Tier::Okay.next(), Some(Tier::Good));
+        assert_eq!(Tier::Bonus.next(), None);
+        assert_eq!(Tier::Bonus.prev(), Some(Tier::Good));
+        assert_eq!(Tier::Bad.prev(), None);
+
+        let query = rewards::table.order(Tier::rank_case(rewards::tier)).select(rewards::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "rewards"."id" FROM "rewards" ORDER BY CASE (CAST("rewards"."tier" AS TEXT)) WHEN ($1) THEN 0 WHEN ($2) THEN 1 WHEN ($3) THEN 1 WHEN ($4) THEN 5 WHEN ($5) THEN 3 ELSE 9223372036854775807 END -- binds: ["bad", "okay", "ok", "bonus", "good"]"#,
+        );
+        let query = rewards::table.order(Mood::rank_case(rewards::mood)).select(rewards::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "rewards"."id" FROM "rewards" ORDER BY CASE LOWER(CAST("rewards"."mood" AS TEXT)) WHEN LOWER($1) THEN 0 WHEN LOWER($2) THEN 1 ELSE 9223372036854775807 END -- binds: ["happy", "o'wary"]"#,
+        );
+    }
+
+    enums::text_enum! {
+        pub enum Journey<Pg> meta JourneyMeta { atomic: bool, category: &'static str } {
+            Solo = "solo" { atomic: false, category: "expeditions" },
+            Wander = "wander" { atomic: true, category: "expeditions" },
+            Ping = "ping" | "p" { atomic: true, category: "general" },
+        }
+    }
+
+    #[test]
+    fn meta() {
+        assert!(!Journey::Solo.atomic());
+        assert_eq!(Journey::Ping.category(), "general");
+        assert_eq!(Journey::Wander.meta(), &JourneyMeta { atomic: true, category: "expeditions" });
+        assert_eq!(
+            Journey::filter_meta(|meta| meta.category == "expeditions").collect::<Vec<_>>(),
+            [Journey::Solo, Journey::Wander],
+        );
+    }
+
+    #[test]
+    fn text_enum() {
+        assert_eq!(Boundary::ALL, [Boundary::Daily, Boundary::Weekly, Boundary::Never]);
+        assert_eq!(Boundary::VARIANTS, ["daily", "weekly", "never"]);
+        assert_eq!(Boundary::Weekly.as_str(), "weekly");
+        assert_eq!(format!("{:>7}", Boundary::Never), "  never");
+        for (variant, text) in Boundary::ALL.iter().zip(Boundary::VARIANTS) {
+            assert_eq!(Boundary::from_str(text), Ok(*variant));
+        }
+        assert_eq!(
+            "Daily".parse::<Boundary>().unwrap_err(),
+            ParseEnumError { value: "Daily".to_owned(), rust_type: std::any::type_name::<Boundary>() },
+        );
+
+        assert_eq!(crate::test::from_sql::<Direction, Text>(b"N").unwrap(), Direction::North);
+        let e = crate::test::from_sql::<Direction, Text>(b"west").unwrap_err();
+        let e = e.downcast_ref::<ConversionError>().unwrap();
+        assert_eq!(e.kind, ConversionErrorKind::Enum(EnumError::Unknown));
+        assert_eq!(e.value, "west");
+        assert!(crate::test::from_sql::<Direction, Text>(&[0xff]).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::num::ParseIntError;
+
 use thiserror::Error;
 
 use diesel::result::Error as DieselError;
@@ -22,6 +24,68 @@ pub enum DieselOr<Logical> {
     Logical(#[from] Logical),
 }
 
+/// A value read from the database that doesn't fit the Rust type it was decoded into.
+///
+/// This is what the `FromSql` impls in this crate fail with, so it can be recovered from
+/// diesel's `DeserializationError` with `downcast_ref::<ConversionError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error)]
+#[error("{kind}: {value} ({sql_type} as {rust_type})")]
+pub struct ConversionError {
+    pub kind: ConversionErrorKind,
+    /// The offending value, as decoded from the database.
+    pub value: String,
+    pub rust_type: &'static str,
+    pub sql_type: &'static str,
+}
+
+impl ConversionError {
+    pub fn new<Rust, Sql>(kind: impl Into<ConversionErrorKind>, value: impl ToString) -> Self {
+        Self {
+            kind: kind.into(),
+            value: value.to_string(),
+            rust_type: std::any::type_name::<Rust>(),
+            sql_type: std::any::type_name::<Sql>(),
+        }
+    }
+
+    /// A decoded value that a `wrap_type!` validator rejected, keeping the validator's message.
+    pub fn invalid<Rust, Sql>(error: impl std::fmt::Display, value: impl std::fmt::Debug) -> Self {
+        Self::new::<Rust, Sql>(ConversionErrorKind::Invalid(error.to_string()), format!("{value:?}"))
+    }
+
+    /// Reports a `ConversionError` as a failure to decode `Rust`, so that errors from an
+    /// intermediate type name the type that was actually being decoded.
+    pub fn retarget<Rust>(mut e: Box<dyn std::error::Error + Send + Sync>) -> Box<dyn std::error::Error + Send + Sync> {
+        if let Some(conversion) = e.downcast_mut::<ConversionError>() {
+            conversion.rust_type = std::any::type_name::<Rust>();
+        }
+        e
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum ConversionErrorKind {
+    #[error(transparent)]
+    U64(#[from] NumericU64Error),
+    #[error(transparent)]
+    U128(#[from] NumericU128Error),
+    #[error(transparent)]
+    I128(#[from] NumericI128Error),
+    #[error(transparent)]
+    U32(#[from] NumericU32Error),
+    #[error(transparent)]
+    U16(#[from] NumericU16Error),
+    #[error(transparent)]
+    U8(#[from] NumericU8Error),
+    /// Rejected by the `validate` block of a `wrap_type!`, with the validator's message.
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Enum(#[from] EnumError),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[derive(Error)]
 pub enum NumericU64Error {
@@ -31,8 +95,30 @@ pub enum NumericU64Error {
     Negative,
     #[error("numeric is decimal")]
     Decimal,
-    #[error("numeric is not a u64")]
-    Unknown,
+    #[error("numeric has too many decimal places")]
+    Precision,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum NumericU128Error {
+    #[error("numeric overflows")]
+    Overflow,
+    #[error("numeric underflows")]
+    Negative,
+    #[error("numeric is decimal")]
+    Decimal,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum NumericI128Error {
+    #[error("numeric overflows")]
+    Overflow,
+    #[error("numeric underflows")]
+    Underflow,
+    #[error("numeric is decimal")]
+    Decimal,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -44,6 +130,33 @@ pub enum NumericU32Error {
     Negative,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum NumericU16Error {
+    #[error("numeric overflows")]
+    Overflow,
+    #[error("numeric underflows")]
+    Negative,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum NumericU8Error {
+    #[error("numeric overflows")]
+    Overflow,
+    #[error("numeric underflows")]
+    Negative,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error)]
+pub enum SignedU64Error {
+    #[error("value overflows")]
+    Overflow,
+    #[error("value is not an integer: {0}")]
+    Parse(#[from] ParseIntError),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[derive(Error)]
 pub enum LevelError {
@@ -52,3 +165,59 @@ pub enum LevelError {
     #[error("numeric underflows")]
     Negative,
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum EnumError {
+    #[error("unknown variant")]
+    Unknown,
+}
+
+/// The error from parsing a `text_enum!`, or from converting a code into an `int_enum!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error)]
+#[error("unknown {rust_type} variant: {value}")]
+pub struct ParseEnumError {
+    pub value: String,
+    pub rust_type: &'static str,
+}
+
+impl ParseEnumError {
+    pub fn new<Rust>(value: impl ToString) -> Self {
+        Self {
+            value: value.to_string(),
+            rust_type: std::any::type_name::<Rust>(),
+        }
+    }
+}
+
+/// A supertype ID that isn't of the subtype it was converted into, from `id_subtype!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Error)]
+#[error("{value} is not a {rust_type}")]
+pub struct DowncastError {
+    pub value: String,
+    pub rust_type: &'static str,
+}
+
+impl DowncastError {
+    pub fn new<Rust>(value: impl ToString) -> Self {
+        Self {
+            value: value.to_string(),
+            rust_type: std::any::type_name::<Rust>(),
+        }
+    }
+}
+
+/// A [`SnowflakeGenerator`](crate::snowflake::SnowflakeGenerator) that can't be made, or can't
+/// make any more IDs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Error)]
+pub enum SnowflakeError {
+    #[error("{worker_bits} worker bits and {sequence_bits} sequence bits leave no room for the current timestamp")]
+    TooManyBits { worker_bits: u32, sequence_bits: u32 },
+    #[error("worker ID {worker_id} doesn't fit in {worker_bits} bits")]
+    WorkerId { worker_id: u64, worker_bits: u32 },
+    #[error("the timestamp no longer fits in {timestamp_bits} bits")]
+    OutOfTime { timestamp_bits: u32 },
+}
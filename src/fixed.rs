@@ -0,0 +1,314 @@
+//! Fixed-point decimals stored as `numeric`, with the number of decimal places part of the type.
+
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Numeric,
+    AsExpression,
+    FromSqlRow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{ConversionError, NumericU64Error},
+    numeric::{self, MagnitudeError, NumericU64Parts},
+    SignedU64,
+};
+
+fn scaled_error<Rust>(e: MagnitudeError, parts: &NumericU64Parts) -> ConversionError {
+    let kind = match e {
+        MagnitudeError::Overflow => NumericU64Error::Overflow,
+        MagnitudeError::Decimal => NumericU64Error::Precision,
+    };
+    ConversionError::new::<Rust, Numeric>(kind, parts.describe())
+}
+
+/// An unsigned decimal with exactly `SCALE` decimal places, held as its integer mantissa, so
+/// `PgFixed::<2>::from_mantissa(1250)` is `12.50`.
+///
+/// Values read from the database with more than `SCALE` significant decimal places are rejected
+/// with [`NumericU64Error::Precision`] instead of being rounded. Like [`SignedU64`], the operators
+/// panic on overflow and the `checked_*` methods don't.
+#[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Numeric)]
+pub struct PgFixed<const SCALE: u32>(u64);
+
+impl<const SCALE: u32> PgFixed<SCALE> {
+    /// The mantissa of `1`, which also checks at compile time that `SCALE` fits in a u64. The
+    /// diesel impls evaluate it too, so an oversized scale can't reach the database.
+    ///
+    /// ```compile_fail
+    /// use diesel::{pg::Pg, serialize::{self, Output, ToSql}, sql_types::Numeric};
+    /// use diesel_pg_type_utils::PgFixed;
+    ///
+    /// let to_sql: for<'b, 'c, 'd> fn(&'b PgFixed<25>, &'d mut Output<'b, 'c, Pg>) -> serialize::Result =
+    ///     ToSql::<Numeric, Pg>::to_sql;
+    /// std::hint::black_box(to_sql);
+    /// ```
+    pub const ONE_MANTISSA: u64 = {
+        assert!(SCALE <= 19, "at most 19 decimal places fit in a u64");
+        10u64.pow(SCALE)
+    };
+
+    pub const ZERO: Self = Self(0);
+
+    pub const fn from_mantissa(mantissa: u64) -> Self {
+        Self(mantissa)
+    }
+
+    pub const fn mantissa(&self) -> u64 {
+        self.0
+    }
+
+    pub fn from_integer(integer: u64) -> Option<Self> {
+        integer.checked_mul(Self::ONE_MANTISSA).map(Self)
+    }
+
+    pub const fn integer_part(&self) -> u64 {
+        self.0 / Self::ONE_MANTISSA
+    }
+
+    /// The digits after the decimal point, as an integer.
+    pub const fn fractional_part(&self) -> u64 {
+        self.0 % Self::ONE_MANTISSA
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn checked_mul(self, factor: u64) -> Option<Self> {
+        self.0.checked_mul(factor).map(Self)
+    }
+}
+
+impl<const SCALE: u32> Add for PgFixed<SCALE> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("PgFixed addition overflowed")
+    }
+}
+
+impl<const SCALE: u32> AddAssign for PgFixed<SCALE> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const SCALE: u32> Sub for PgFixed<SCALE> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("PgFixed subtraction overflowed")
+    }
+}
+
+impl<const SCALE: u32> SubAssign for PgFixed<SCALE> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const SCALE: u32> Mul<u64> for PgFixed<SCALE> {
+    type Output = Self;
+
+    fn mul(self, factor: u64) -> Self {
+        self.checked_mul(factor).expect("PgFixed multiplication overflowed")
+    }
+}
+
+impl<const SCALE: u32> Display for PgFixed<SCALE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = if SCALE == 0 {
+            self.integer_part().to_string()
+        } else {
+            format!("{}.{:0width$}", self.integer_part(), self.fractional_part(), width = SCALE as usize)
+        };
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl<const SCALE: u32> FromSql<Numeric, Pg> for PgFixed<SCALE> {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let _ = Self::ONE_MANTISSA;
+        let parts = NumericU64Parts::from_sql_scaled(bytes, SCALE)?;
+        if let Ok(total) = parts.total && parts.is_strictly_negative() {
+            let value = PgSignedFixed::<SCALE>::from_mantissa(SignedU64::new(total, true));
+            return Err(ConversionError::new::<Self, Numeric>(NumericU64Error::Negative, value).into());
+        }
+        Ok(Self(parts.total.map_err(|e| scaled_error::<Self>(e, &parts))?))
+    }
+}
+
+impl<const SCALE: u32> ToSql<Numeric, Pg> for PgFixed<SCALE> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let _ = Self::ONE_MANTISSA;
+        numeric::write_scaled(out, self.0, false, u16::try_from(SCALE)?)?;
+        Ok(IsNull::No)
+    }
+}
+
+/// A signed [`PgFixed`], held as a [`SignedU64`] mantissa.
+#[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Numeric)]
+pub struct PgSignedFixed<const SCALE: u32>(SignedU64);
+
+impl<const SCALE: u32> PgSignedFixed<SCALE> {
+    pub const ZERO: Self = Self(SignedU64::ZERO);
+
+    pub const fn from_mantissa(mantissa: SignedU64) -> Self {
+        Self(mantissa)
+    }
+
+    pub const fn mantissa(&self) -> SignedU64 {
+        self.0
+    }
+
+    pub fn from_integer(integer: SignedU64) -> Option<Self> {
+        integer.checked_mul(PgFixed::<SCALE>::ONE_MANTISSA.into()).map(Self)
+    }
+
+    pub const fn is_negative(&self) -> bool {
+        self.0.is_negative()
+    }
+
+    /// The absolute value.
+    pub const fn magnitude(&self) -> PgFixed<SCALE> {
+        PgFixed(self.0.total())
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn checked_mul(self, factor: SignedU64) -> Option<Self> {
+        self.0.checked_mul(factor).map(Self)
+    }
+}
+
+impl<const SCALE: u32> From<PgFixed<SCALE>> for PgSignedFixed<SCALE> {
+    fn from(f: PgFixed<SCALE>) -> Self {
+        Self(f.0.into())
+    }
+}
+
+impl<const SCALE: u32> Neg for PgSignedFixed<SCALE> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl<const SCALE: u32> Add for PgSignedFixed<SCALE> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl<const SCALE: u32> AddAssign for PgSignedFixed<SCALE> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const SCALE: u32> Sub for PgSignedFixed<SCALE> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl<const SCALE: u32> SubAssign for PgSignedFixed<SCALE> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const SCALE: u32> Mul<SignedU64> for PgSignedFixed<SCALE> {
+    type Output = Self;
+
+    fn mul(self, factor: SignedU64) -> Self {
+        Self(self.0 * factor)
+    }
+}
+
+impl<const SCALE: u32> Display for PgSignedFixed<SCALE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let magnitude = self.magnitude();
+        let digits = if SCALE == 0 {
+            magnitude.integer_part().to_string()
+        } else {
+            format!("{}.{:0width$}", magnitude.integer_part(), magnitude.fractional_part(), width = SCALE as usize)
+        };
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+
+impl<const SCALE: u32> FromSql<Numeric, Pg> for PgSignedFixed<SCALE> {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let _ = PgFixed::<SCALE>::ONE_MANTISSA;
+        let parts = NumericU64Parts::from_sql_scaled(bytes, SCALE)?;
+        let total = parts.total.map_err(|e| scaled_error::<Self>(e, &parts))?;
+        Ok(Self(SignedU64::new(total, parts.is_negative)))
+    }
+}
+
+impl<const SCALE: u32> ToSql<Numeric, Pg> for PgSignedFixed<SCALE> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let _ = PgFixed::<SCALE>::ONE_MANTISSA;
+        numeric::write_scaled(out, self.0.total(), self.0.is_negative(), u16::try_from(SCALE)?)?;
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{PgFixed, PgSignedFixed, SignedU64};
+
+    #[test]
+    fn display() {
+        assert_eq!(PgFixed::<2>::from_mantissa(1250).to_string(), "12.50");
+        assert_eq!(PgFixed::<2>::from_mantissa(5).to_string(), "0.05");
+        assert_eq!(PgFixed::<0>::from_mantissa(5).to_string(), "5");
+        assert_eq!(PgFixed::<3>::ZERO.to_string(), "0.000");
+        assert_eq!(PgSignedFixed::<2>::from_mantissa(SignedU64::new(1250, true)).to_string(), "-12.50");
+        assert_eq!(PgSignedFixed::<2>::from_mantissa(SignedU64::new(0, true)).to_string(), "0.00");
+    }
+
+    #[test]
+    fn arithmetic() {
+        let price = PgFixed::<2>::from_mantissa(1250);
+        assert_eq!(price + price, PgFixed::from_mantissa(2500));
+        assert_eq!(price * 3, PgFixed::from_mantissa(3750));
+        assert_eq!(PgFixed::<2>::from_integer(12), Some(PgFixed::from_mantissa(1200)));
+        assert_eq!(PgFixed::<2>::from_integer(u64::MAX), None);
+        assert_eq!(PgFixed::<2>::ZERO.checked_sub(price), None);
+
+        let debt = PgSignedFixed::from(PgFixed::<2>::ZERO) - PgSignedFixed::from(price);
+        assert_eq!(debt, -PgSignedFixed::from(price));
+        assert_eq!((debt * SignedU64::from(-2i64)).to_string(), "25.00");
+    }
+}
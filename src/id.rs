@@ -0,0 +1,355 @@
+//! Primary keys typed by the table they belong to.
+
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    expression::{is_aggregate, AppearsOnTable, AsExpression, Expression, SelectableExpression, TypedExpressionType, ValidGrouping},
+    pg::Pg,
+    query_builder::{AstPass, QueryFragment, QueryId},
+    serialize::{self, Output, ToSql},
+    sql_types::{is_nullable::NotNull, BigInt, HasSqlType, Integer, Nullable, SingleValue, SmallInt, SqlOrd, SqlType},
+    FromSqlRow,
+    QueryResult,
+    Table,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The ID of a row in `Table`, which is meant to be the `table` struct of a diesel `table!`, as in
+/// `Id<users::table>`. IDs of different tables are different types, so they can't be mixed up.
+///
+/// `Repr` is the integer the ID is stored as: `i64` for `BIGINT` (the default), `i32` for `INTEGER`
+/// or `i16` for `SMALLINT`. Apart from the SQL expressions, the impls only depend on `Repr`, so
+/// `Table` doesn't need to implement anything.
+///
+/// In SQL, an `Id<Table>` is only an expression of [`IdType<Table>`], the type to give the ID
+/// columns in `table!`, so comparing a column with the ID of another table doesn't compile:
+///
+/// ```compile_fail
+/// use diesel::prelude::*;
+/// use diesel_pg_type_utils::Id;
+///
+/// diesel::table! {
+///     use diesel::sql_types::*;
+///     use diesel_pg_type_utils::id::IdType;
+///
+///     users (id) {
+///         id -> IdType<crate::users::table>,
+///     }
+/// }
+///
+/// diesel::table! {
+///     use diesel::sql_types::*;
+///     use diesel_pg_type_utils::id::IdType;
+///
+///     guilds (id) {
+///         id -> IdType<crate::guilds::table>,
+///     }
+/// }
+///
+/// fn main() {
+///     let _ = users::table.filter(users::id.eq(Id::<guilds::table>::new(3)));
+/// }
+/// ```
+#[derive(FromSqlRow)]
+pub struct Id<Table, Repr = i64>(Repr, PhantomData<fn() -> Table>);
+
+/// The SQL type of a column holding [`Id<Table, _>`](Id)s, stored as `ST`. It goes in `table!` in
+/// place of the integer type, as in `id -> IdType<crate::schema::users::table, Integer>`, and
+/// foreign keys use the table they point to. The paths have to be absolute, since `table!` expands
+/// the column types in more than one module.
+///
+/// The plain integers can still be loaded from these columns, but only `Id`s can be compared with
+/// them or inserted into them.
+#[derive(Debug, Copy, Clone)]
+pub struct IdType<Table, ST = BigInt>(PhantomData<fn() -> (Table, ST)>);
+
+impl<T: Table + 'static, ST: SqlType + 'static> SqlType for IdType<T, ST> {
+    type IsNull = NotNull;
+}
+
+impl<T: Table + 'static, ST: SingleValue + 'static> SingleValue for IdType<T, ST> {}
+
+impl<T: Table + 'static, ST: SqlOrd + 'static> SqlOrd for IdType<T, ST> {}
+
+impl<T: 'static, ST: 'static> QueryId for IdType<T, ST> {
+    type QueryId = Self;
+
+    const HAS_STATIC_QUERY_ID: bool = true;
+}
+
+impl<T, ST> HasSqlType<IdType<T, ST>> for Pg
+where
+    Pg: HasSqlType<ST>,
+{
+    fn metadata(lookup: &mut Self::MetadataLookup) -> Self::TypeMetadata {
+        <Pg as HasSqlType<ST>>::metadata(lookup)
+    }
+}
+
+/// An [`Id`], or a reference to one, bound as a query parameter of type `ST`. This is what `Id`s
+/// become when compared with or inserted into an [`IdType`] column.
+#[derive(Debug, Copy, Clone)]
+pub struct IdBind<ST, V> {
+    value: V,
+    _sql_type: PhantomData<ST>,
+}
+
+impl<ST, V> IdBind<ST, V> {
+    fn new(value: V) -> Self {
+        Self { value, _sql_type: PhantomData }
+    }
+}
+
+impl<ST: SqlType + TypedExpressionType, V> Expression for IdBind<ST, V> {
+    type SqlType = ST;
+}
+
+impl<ST, V, QS: ?Sized> AppearsOnTable<QS> for IdBind<ST, V> where Self: Expression {}
+
+impl<ST, V, QS: ?Sized> SelectableExpression<QS> for IdBind<ST, V> where Self: Expression {}
+
+impl<ST, V, GB> ValidGrouping<GB> for IdBind<ST, V> {
+    type IsAggregate = is_aggregate::Never;
+}
+
+impl<ST: QueryId, V> QueryId for IdBind<ST, V> {
+    type QueryId = IdBind<ST::QueryId, ()>;
+
+    const HAS_STATIC_QUERY_ID: bool = ST::HAS_STATIC_QUERY_ID;
+}
+
+impl<ST, V: ToSql<ST, Pg>> QueryFragment<Pg> for IdBind<ST, V>
+where
+    Pg: HasSqlType<ST>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, Pg>) -> QueryResult<()> {
+        out.push_bind_param::<ST, V>(&self.value)
+    }
+}
+
+impl<Table, Repr> Id<Table, Repr> {
+    pub const fn new(id: Repr) -> Self {
+        Self(id, PhantomData)
+    }
+
+    pub fn get(self) -> Repr {
+        self.0
+    }
+
+    pub fn inner(&self) -> &Repr {
+        &self.0
+    }
+
+    /// The same ID, for another table. For tables that share keys, like a table extending another.
+    pub fn cast<Other>(self) -> Id<Other, Repr> {
+        Id(self.0, PhantomData)
+    }
+}
+
+impl<Table, Repr: Copy> Copy for Id<Table, Repr> {}
+
+impl<Table, Repr: Clone> Clone for Id<Table, Repr> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<Table, Repr: Debug> Debug for Id<Table, Repr> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Id").field(&self.0).finish()
+    }
+}
+
+impl<Table, Repr: Display> Display for Id<Table, Repr> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<Table, Repr: PartialEq> PartialEq for Id<Table, Repr> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Table, Repr: Eq> Eq for Id<Table, Repr> {}
+
+impl<Table, Repr: PartialOrd> PartialOrd for Id<Table, Repr> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<Table, Repr: Ord> Ord for Id<Table, Repr> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<Table, Repr: Hash> Hash for Id<Table, Repr> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<Table, Repr: Serialize> Serialize for Id<Table, Repr> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, Table, Repr: Deserialize<'de>> Deserialize<'de> for Id<Table, Repr> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Repr::deserialize(deserializer).map(Self::new)
+    }
+}
+
+macro_rules! id_repr {
+    ($repr:ty, $sql:ty) => {
+        impl<Table> From<Id<Table, $repr>> for $repr {
+            fn from(id: Id<Table, $repr>) -> Self {
+                id.0
+            }
+        }
+
+        impl<T: Table + 'static> AsExpression<IdType<T, $sql>> for Id<T, $repr> {
+            type Expression = IdBind<IdType<T, $sql>, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                IdBind::new(self)
+            }
+        }
+
+        impl<'a, T: Table + 'static> AsExpression<IdType<T, $sql>> for &'a Id<T, $repr> {
+            type Expression = IdBind<IdType<T, $sql>, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                IdBind::new(self)
+            }
+        }
+
+        impl<T: Table + 'static> AsExpression<Nullable<IdType<T, $sql>>> for Id<T, $repr> {
+            type Expression = IdBind<Nullable<IdType<T, $sql>>, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                IdBind::new(self)
+            }
+        }
+
+        impl<'a, T: Table + 'static> AsExpression<Nullable<IdType<T, $sql>>> for &'a Id<T, $repr> {
+            type Expression = IdBind<Nullable<IdType<T, $sql>>, Self>;
+
+            fn as_expression(self) -> Self::Expression {
+                IdBind::new(self)
+            }
+        }
+
+        impl<T, DB: Backend> ToSql<Nullable<IdType<T, $sql>>, DB> for Id<T, $repr>
+        where
+            $repr: ToSql<$sql, DB>,
+        {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                <$repr as ToSql<$sql, DB>>::to_sql(&self.0, out)
+            }
+        }
+
+        impl<Table, DB: Backend> FromSql<IdType<Table, $sql>, DB> for Id<Table, $repr>
+        where
+            $repr: FromSql<$sql, DB>,
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+                <$repr as FromSql<$sql, DB>>::from_sql(bytes).map(Self::new)
+            }
+        }
+
+        impl<Table, DB: Backend> FromSql<IdType<Table, $sql>, DB> for $repr
+        where
+            $repr: FromSql<$sql, DB>,
+        {
+            fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+                <$repr as FromSql<$sql, DB>>::from_sql(bytes)
+            }
+        }
+
+        impl<Table, DB: Backend> ToSql<IdType<Table, $sql>, DB> for Id<Table, $repr>
+        where
+            $repr: ToSql<$sql, DB>,
+        {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+                <$repr as ToSql<$sql, DB>>::to_sql(&self.0, out)
+            }
+        }
+    };
+}
+
+id_repr!(i16, SmallInt);
+id_repr!(i32, Integer);
+id_repr!(i64, BigInt);
+
+#[cfg(test)]
+mod test {
+    use diesel::{debug_query, pg::Pg, prelude::*};
+
+    use crate::{id::IdType, Id};
+
+    diesel::table! {
+        use diesel::sql_types::*;
+        use crate::id::IdType;
+
+        users (id) {
+            id -> IdType<crate::id::test::users::table>,
+            guild_id -> IdType<crate::id::test::guilds::table, Integer>,
+            invited_by -> Nullable<IdType<crate::id::test::users::table>>,
+        }
+    }
+
+    diesel::table! {
+        use diesel::sql_types::*;
+        use crate::id::IdType;
+
+        guilds (id) {
+            id -> IdType<crate::id::test::guilds::table, Integer>,
+        }
+    }
+
+    diesel::joinable!(users -> guilds (guild_id));
+    diesel::allow_tables_to_appear_in_same_query!(users, guilds);
+
+    #[test]
+    fn id() {
+        let user = Id::<users::table>::new(7);
+        let guild = Id::<guilds::table, i32>::new(3);
+        let query = users::table
+            .filter(users::id.eq(user))
+            .filter(users::guild_id.eq(guild))
+            .select(users::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "users"."id" FROM "users" WHERE (("users"."id" = $1) AND ("users"."guild_id" = $2)) -- binds: [Id(7), Id(3)]"#,
+        );
+
+        let query = users::table.filter(users::invited_by.eq(&user)).select(users::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "users"."id" FROM "users" WHERE ("users"."invited_by" = $1) -- binds: [Id(7)]"#,
+        );
+        let insert = diesel::insert_into(users::table).values((users::id.eq(user), users::guild_id.eq(&guild)));
+        assert_eq!(
+            debug_query::<Pg, _>(&insert).to_string(),
+            r#"INSERT INTO "users" ("id", "guild_id") VALUES ($1, $2) -- binds: [Id(7), Id(3)]"#,
+        );
+
+        let decoded: Id<users::table> = crate::test::from_sql::<_, IdType<users::table>>(&7i64.to_be_bytes()).unwrap();
+        assert_eq!(decoded, user);
+        assert_eq!(i64::from(user), 7);
+        assert_eq!(user.cast::<guilds::table>().get(), 7);
+        assert_eq!(user.to_string(), "7");
+    }
+}
@@ -1,16 +1,30 @@
-use std::{cmp::Ordering, hash::Hash, ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign}};
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+    hash::Hash,
+    num::{IntErrorKind, ParseIntError},
+    ops::{Add, AddAssign, Mul, Neg, RangeInclusive, Sub, SubAssign},
+    str::FromStr,
+};
 
 use treeerror::from;
 
-use bigdecimal::{Zero, BigDecimal};
+use bigdecimal::Zero;
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
 use diesel_async::AsyncConnection;
 // Re-export so that macros work.
 pub use serde;
 pub use diesel;
+pub use diesel_async;
+pub use diesel_pg_type_utils_derive::PgWrap;
+// So that derives expanded inside this crate can refer to it by name.
+extern crate self as diesel_pg_type_utils;
 use diesel::{
     sql_types::{
         Numeric,
         BigInt,
+        Integer,
+        SmallInt,
     },
     pg::Pg,
     AsExpression,
@@ -20,51 +34,150 @@ use serde::{Deserialize, Serialize};
 
 pub mod error;
 pub mod wrap;
+mod numeric;
+pub mod fixed;
+pub mod rounding;
+pub mod enums;
+pub mod id;
+pub mod snowflake;
+#[cfg(feature = "serenity")]
+pub mod discord;
+
+pub use fixed::{PgFixed, PgSignedFixed};
+pub use rounding::PgU64Rounded;
+pub use id::Id;
+pub use snowflake::Snowflake;
 
 pub mod ext;
 
-use crate::error::{
-    NumericU64Error,
-    NumericU32Error,
+use crate::{
+    error::{
+        ConversionError,
+        NumericU64Error,
+        NumericU128Error,
+        NumericI128Error,
+        NumericU32Error,
+        NumericU16Error,
+        NumericU8Error,
+        SignedU64Error,
+    },
+    numeric::{
+        MagnitudeError,
+        NumericU64Parts,
+        NumericU128Parts,
+    },
 };
 
 wrap_type! {
     #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    PgU64<Pg>(Numeric > bigdecimal::BigDecimal > u64)
-    |big| {
-        use bigdecimal::{
-            ToPrimitive,
-            Signed,
-        };
-        if let Some(value) = big.to_u64() {
-            Ok(value)
-        } else if big > u64::MAX.into() {
-            Err(NumericU64Error::Overflow)
-        } else if big.is_negative() {
-            Err(NumericU64Error::Negative)
-        } else if !big.is_integer() {
-            Err(NumericU64Error::Decimal)
-        } else {
-            Err(NumericU64Error::Unknown)?
-        }?
+    PgU64<Pg>(Numeric > NumericU64Parts > u64)
+    |parts| {
+        unsigned_u64::<PgU64>(parts)?
     }
     |b| {
-        use bigdecimal::BigDecimal;
-        &BigDecimal::from(b)
+        &NumericU64Parts::new(b, false)
     }
 }
 
 // This is okay, since PgU64 exactly match the domain.
 from!(PgU64 = |u: u64| Self(u));
 
+/// The conversion shared by `PgU64` and `PgU64Rounded`.
+fn unsigned_u64<Rust>(parts: NumericU64Parts) -> Result<u64, ConversionError> {
+    if parts.is_strictly_negative() {
+        return Err(ConversionError::new::<Rust, Numeric>(NumericU64Error::Negative, parts.describe()));
+    }
+    parts.total.map_err(|e| ConversionError::new::<Rust, Numeric>(NumericU64Error::from(e), parts.describe()))
+}
+
+wrap_type! {
+    #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    PgU128<Pg>(Numeric > NumericU128Parts > u128)
+    |parts| {
+        if parts.is_strictly_negative() {
+            return Err(ConversionError::new::<PgU128, Numeric>(NumericU128Error::Negative, parts.describe()).into());
+        }
+        parts.total.map_err(|e| ConversionError::new::<PgU128, Numeric>(NumericU128Error::from(e), parts.describe()))?
+    }
+    |b| {
+        &NumericU128Parts::new(b, false)
+    }
+}
+
+// This is okay, since PgU128 exactly match the domain.
+from!(PgU128 = |u: u128| Self(u));
+
+wrap_type! {
+    #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    PgI128<Pg>(Numeric > NumericU128Parts > i128)
+    |parts| {
+        let error = |kind| ConversionError::new::<PgI128, Numeric>(kind, parts.describe());
+        let total = parts.total.map_err(|e| error(match e {
+            MagnitudeError::Overflow if parts.is_negative => NumericI128Error::Underflow,
+            MagnitudeError::Overflow => NumericI128Error::Overflow,
+            MagnitudeError::Decimal => NumericI128Error::Decimal,
+        }))?;
+        if parts.is_negative {
+            0i128.checked_sub_unsigned(total).ok_or_else(|| error(NumericI128Error::Underflow))?
+        } else {
+            i128::try_from(total).map_err(|_| error(NumericI128Error::Overflow))?
+        }
+    }
+    |b| {
+        &NumericU128Parts::new(b.unsigned_abs(), b.is_negative())
+    }
+}
+
+// This is okay, since PgI128 exactly match the domain.
+from!(PgI128 = |i: i128| Self(i));
+
+wrap_type! {
+    #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    PgU64Bigint<Pg>(BigInt > i64 > u64)
+    |value| {
+        PgU64Bigint::decode(value)
+    }
+    |b| {
+        &PgU64Bigint::encode(b)
+    }
+}
+
+/// Stores a `u64` in a `BIGINT` by flipping the sign bit, so that ordering in Postgres matches
+/// ordering in Rust: `0` is stored as `i64::MIN` and `u64::MAX` as `i64::MAX`.
+///
+/// Since the stored value is biased, comparisons against the column in a `filter` need to go
+/// through [`PgU64Bigint::encode`] (or the wrapper itself, which is an expression of `BigInt`).
+impl PgU64Bigint {
+    const BIAS: u64 = 1 << 63;
+
+    /// The `BIGINT` a `u64` is stored as.
+    pub const fn encode(u: u64) -> i64 {
+        (u ^ Self::BIAS) as i64
+    }
+
+    /// The `u64` a stored `BIGINT` represents.
+    pub const fn decode(i: i64) -> u64 {
+        (i as u64) ^ Self::BIAS
+    }
+
+    /// Encodes both ends of an inclusive range, for use with `between`.
+    pub fn encode_bounds(range: RangeInclusive<u64>) -> (i64, i64) {
+        let (start, end) = range.into_inner();
+        (Self::encode(start), Self::encode(end))
+    }
+}
+
+// This is okay, since PgU64Bigint exactly match the domain.
+from!(PgU64Bigint = |u: u64| Self(u));
+
 wrap_type! {
     #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
     PgU32<Pg>(BigInt > i64 > u32)
     |value| {
         if value > u32::MAX.into() {
-            return Err(NumericU32Error::Overflow.into());
+            return Err(ConversionError::new::<PgU32, BigInt>(NumericU32Error::Overflow, value).into());
         } else if value.is_negative() {
-            return Err(NumericU32Error::Negative.into());
+            return Err(ConversionError::new::<PgU32, BigInt>(NumericU32Error::Negative, value).into());
         }
         value as u32
     }
@@ -76,18 +189,193 @@ wrap_type! {
 // This is okay, since PgU32 exactly match the domain.
 from!(PgU32 = |u: u32| Self(u));
 
-#[derive(Debug, Default, Copy, Clone)]
+wrap_type! {
+    #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    PgU16<Pg>(Integer > i32 > u16)
+    |value| {
+        if value > u16::MAX.into() {
+            return Err(ConversionError::new::<PgU16, Integer>(NumericU16Error::Overflow, value).into());
+        } else if value.is_negative() {
+            return Err(ConversionError::new::<PgU16, Integer>(NumericU16Error::Negative, value).into());
+        }
+        value as u16
+    }
+    |b| {
+        &(b as i32)
+    }
+}
+
+// This is okay, since PgU16 exactly match the domain.
+from!(PgU16 = |u: u16| Self(u));
+
+wrap_type! {
+    #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    PgU8<Pg>(SmallInt > i16 > u8)
+    |value| {
+        if value > u8::MAX.into() {
+            return Err(ConversionError::new::<PgU8, SmallInt>(NumericU8Error::Overflow, value).into());
+        } else if value.is_negative() {
+            return Err(ConversionError::new::<PgU8, SmallInt>(NumericU8Error::Negative, value).into());
+        }
+        value as u8
+    }
+    |b| {
+        &(b as i16)
+    }
+}
+
+// This is okay, since PgU8 exactly match the domain.
+from!(PgU8 = |u: u8| Self(u));
+
+/// A `u64` magnitude with a sign, stored as a `numeric`.
+///
+/// Zero is always positive, so the derived equality and hashing are exact and negative zero never
+/// reaches serde or the database.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 #[derive(Serialize, Deserialize)]
+#[serde(from = "RawSignedU64")]
 #[derive(AsExpression, FromSqlRow)]
 #[diesel(sql_type = Numeric)]
 pub struct SignedU64 {
+    total: u64,
+    is_negative: bool,
+}
+
+/// The fields of a [`SignedU64`], with no invariants attached.
+///
+/// This is the migration path for code that used to build `SignedU64` literally:
+/// `SignedU64 { total, is_negative }` becomes `RawSignedU64 { total, is_negative }.into()`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+pub struct RawSignedU64 {
     pub total: u64,
     pub is_negative: bool,
 }
 
+impl From<RawSignedU64> for SignedU64 {
+    fn from(raw: RawSignedU64) -> Self {
+        Self::new(raw.total, raw.is_negative)
+    }
+}
+
+impl From<SignedU64> for RawSignedU64 {
+    fn from(v: SignedU64) -> Self {
+        Self {
+            total: v.total,
+            is_negative: v.is_negative,
+        }
+    }
+}
+
+impl SignedU64 {
+    pub const ZERO: Self = Self::new(0, false);
+
+    /// Negative zero is turned into zero.
+    pub const fn new(total: u64, is_negative: bool) -> Self {
+        Self {
+            total,
+            is_negative: is_negative && total != 0,
+        }
+    }
+
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Never true for zero.
+    pub const fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+}
+
+/// # Overflow
+///
+/// The operators (`+`, `-`, `*` and their assigning forms) panic when the magnitude overflows a
+/// `u64`, in both debug and release builds, rather than silently wrapping a balance. Use the
+/// `checked_*`, `saturating_*` or `overflowing_*` methods to handle overflow explicitly.
+impl SignedU64 {
+    /// Adds, returning the result with its magnitude wrapped around `u64::MAX`, and whether it
+    /// overflowed. Adding values of opposite signs never overflows.
+    pub fn overflowing_add(self, other: Self) -> (Self, bool) {
+        if self.is_negative == other.is_negative {
+            let (total, overflowed) = self.total.overflowing_add(other.total);
+            (Self::new(total, self.is_negative), overflowed)
+        } else if self.total >= other.total {
+            (Self::new(self.total - other.total, self.is_negative), false)
+        } else {
+            (Self::new(other.total - self.total, other.is_negative), false)
+        }
+    }
+
+    pub fn overflowing_sub(self, other: Self) -> (Self, bool) {
+        self.overflowing_add(-other)
+    }
+
+    pub fn overflowing_mul(self, other: Self) -> (Self, bool) {
+        let (total, overflowed) = self.total.overflowing_mul(other.total);
+        (Self::new(total, self.is_negative ^ other.is_negative), overflowed)
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        match self.overflowing_add(other) {
+            (value, false) => Some(value),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        match self.overflowing_mul(other) {
+            (value, false) => Some(value),
+            (_, true) => None,
+        }
+    }
+
+    /// Clamps the magnitude to `u64::MAX`, keeping the sign of the true result.
+    pub fn saturating_add(self, other: Self) -> Self {
+        match self.overflowing_add(other) {
+            (value, false) => value,
+            // Only values of the same sign can overflow.
+            (_, true) => Self::new(u64::MAX, self.is_negative),
+        }
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        self.saturating_add(-other)
+    }
+
+    pub fn saturating_mul(self, other: Self) -> Self {
+        match self.overflowing_mul(other) {
+            (value, false) => value,
+            (_, true) => Self::new(u64::MAX, self.is_negative ^ other.is_negative),
+        }
+    }
+}
+
+impl CheckedAdd for SignedU64 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        SignedU64::checked_add(*self, *other)
+    }
+}
+
+impl CheckedSub for SignedU64 {
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        SignedU64::checked_sub(*self, *other)
+    }
+}
+
+impl CheckedMul for SignedU64 {
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        SignedU64::checked_mul(*self, *other)
+    }
+}
+
 impl SubAssign for SignedU64 {
     fn sub_assign(&mut self, other: Self) {
-        *self += -other;
+        *self = *self - other;
     }
 }
 
@@ -95,7 +383,7 @@ impl Sub for SignedU64 {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        self + -other
+        self.checked_sub(other).expect("SignedU64 subtraction overflowed")
     }
 }
 
@@ -103,23 +391,13 @@ impl Add for SignedU64 {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        // implicit copy
-        let mut cloned = self;
-        cloned += other;
-        cloned
+        self.checked_add(other).expect("SignedU64 addition overflowed")
     }
 }
 
 impl AddAssign for SignedU64 {
     fn add_assign(&mut self, other: Self) {
-        if self.is_negative == other.is_negative {
-            self.total += other.total;
-        } else if self.total >= other.total {
-            self.total -= other.total;
-        } else {
-            self.total = other.total - self.total;
-            self.is_negative = other.is_negative;
-        }
+        *self = *self + other;
     }
 }
 
@@ -127,19 +405,13 @@ impl Neg for SignedU64 {
     type Output = Self;
 
     fn neg(self) -> Self {
-        Self {
-            total: self.total,
-            is_negative: !self.is_negative,
-        }
+        Self::new(self.total, !self.is_negative)
     }
 }
 
 impl Zero for SignedU64 {
     fn zero() -> Self {
-        Self {
-            total: 0,
-            is_negative: false,
-        }
+        Self::ZERO
     }
 
     fn is_zero(&self) -> bool {
@@ -149,10 +421,6 @@ impl Zero for SignedU64 {
 
 impl Ord for SignedU64 {
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.total == 0 && other.total == 0 {
-            return Ordering::Equal;
-        }
-
         match (self.is_negative, other.is_negative) {
             (true, false) => {
                 Ordering::Less
@@ -176,94 +444,77 @@ impl PartialOrd for SignedU64 {
     }
 }
 
-impl PartialEq for SignedU64 {
-    fn eq(&self, other: &Self) -> bool {
-        if self.total == 0 && other.total == 0 {
-            return true;
-        }
-
-        self.is_negative == other.is_negative && self.total == other.total
-    }
-}
-
-impl Eq for SignedU64 {}
-
-impl Hash for SignedU64 {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        if self.total == 0 {
-            0u64.hash(state);
-            false.hash(state);
-        } else {
-            self.total.hash(state);
-            self.is_negative.hash(state);
-        }
-    }
-}
-
 impl Mul for SignedU64 {
     type Output = SignedU64;
     fn mul(self, other: Self) -> Self::Output {
-        let is_negative = self.is_negative ^ other.is_negative;
-        let total = self.total * other.total;
-        Self {
-            total,
-            is_negative,
-        }
+        self.checked_mul(other).expect("SignedU64 multiplication overflowed")
     }
 }
 
 impl Mul<u64> for SignedU64 {
     type Output = SignedU64;
     fn mul(self, other: u64) -> Self::Output {
-        let total = self.total * other;
-        Self {
-            total,
-            is_negative: self.is_negative,
-        }
+        self * SignedU64::from(other)
     }
 }
 
 impl_sql_convert!(
     <Pg>
-    Numeric > BigDecimal > SignedU64
-    |big| {
-        use bigdecimal::{
-            ToPrimitive,
-            Signed,
-        };
-        if let Some(value) = big.abs().to_u64() {
-            if big.is_negative() {
-                Ok(SignedU64 {
-                    is_negative: true,
-                    total: value,
-                })
-            } else {
-                Ok(SignedU64 {
-                    is_negative: false,
-                    total: value,
-                })
-            }
-        } else if big > u64::MAX.into() {
-            Err(NumericU64Error::Overflow)
-        } else if !big.is_integer() {
-            Err(NumericU64Error::Decimal)
-        } else {
-            Err(NumericU64Error::Unknown)
-        }?
+    Numeric > NumericU64Parts > SignedU64
+    |parts| {
+        let total = parts.total.map_err(|e| {
+            ConversionError::new::<SignedU64, Numeric>(NumericU64Error::from(e), parts.describe())
+        })?;
+        SignedU64::new(total, parts.is_negative)
     }
     |v| {
+        NumericU64Parts::new(v.total, v.is_negative)
+    }
+);
+
+from!(SignedU64 = |u: u64| Self::new(u, false));
+
+// Every i64 fits, so this also provides an infallible `TryFrom<i64>`.
+from!(SignedU64 = |i: i64| Self::new(i.unsigned_abs(), i < 0));
+
+from!(SignedU64 = |u: PgU64| Self::new(u.0, false));
+
+impl From<SignedU64> for i128 {
+    fn from(v: SignedU64) -> Self {
         if v.is_negative {
-            BigDecimal::from(v.total).neg()
+            -i128::from(v.total)
         } else {
-            BigDecimal::from(v.total)
+            i128::from(v.total)
         }
     }
-);
+}
+
+impl TryFrom<i128> for SignedU64 {
+    type Error = SignedU64Error;
+
+    fn try_from(i: i128) -> Result<Self, Self::Error> {
+        let total = u64::try_from(i.unsigned_abs()).map_err(|_| SignedU64Error::Overflow)?;
+        Ok(Self::new(total, i < 0))
+    }
+}
+
+impl FromStr for SignedU64 {
+    type Err = SignedU64Error;
 
-from!(SignedU64 = |u: u64| Self {
-    total: u,
-    is_negative: false,
-});
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value: i128 = s.parse().map_err(|e: ParseIntError| match e.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => SignedU64Error::Overflow,
+            _ => SignedU64Error::Parse(e),
+        })?;
+        Self::try_from(value)
+    }
+}
+
+impl Display for SignedU64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.is_negative, "", &self.total.to_string())
+    }
+}
 
 
 pub trait PgC: AsyncConnection<Backend = Pg> + 'static {}
@@ -271,35 +522,217 @@ impl <C: AsyncConnection<Backend = Pg> + 'static> PgC for C {}
 
 #[cfg(test)]
 mod test {
+    use diesel::{
+        deserialize::FromSql,
+        expression::{AsExpression, TypedExpressionType},
+        pg::{Pg, PgMetadataLookup, PgTypeMetadata, PgValue},
+        query_builder::{bind_collector::RawBytesBindCollector, QueryFragment},
+        sql_types::SqlType,
+    };
+
+    /// Decodes `bytes` the way diesel does a `ST` value read from Postgres.
+    pub(crate) fn from_sql<T: FromSql<ST, Pg>, ST>(bytes: &[u8]) -> diesel::deserialize::Result<T> {
+        let oid = std::num::NonZeroU32::MIN;
+        T::from_sql(PgValue::new(bytes, &oid))
+    }
+
+    /// Encodes `value` the way diesel does when binding it as a `ST`.
+    pub(crate) fn to_sql<ST, T>(value: T) -> Vec<u8>
+    where
+        ST: SqlType + TypedExpressionType,
+        T: AsExpression<ST>,
+        T::Expression: QueryFragment<Pg>,
+    {
+        struct BuiltIn;
+
+        impl PgMetadataLookup for BuiltIn {
+            fn lookup_type(&mut self, type_name: &str, _: Option<&str>) -> PgTypeMetadata {
+                unreachable!("tests only encode built-in types, not {type_name}")
+            }
+        }
+
+        let expression = value.as_expression();
+        let mut binds = RawBytesBindCollector::<Pg>::new();
+        expression.collect_binds(&mut binds, &mut BuiltIn, &Pg).unwrap();
+        binds.binds.pop().flatten().unwrap()
+    }
+
+    mod conversion_error {
+        use diesel::{pg::Pg, sql_types::Numeric};
+
+        use crate::{PgU64, wrap, error::{ConversionError, ConversionErrorKind, NumericU64Error}};
+
+        wrap::wrap_u64!(ItemAmount<Pg>);
+
+        #[test]
+        fn display() {
+            let e = ConversionError::new::<u64, Numeric>(NumericU64Error::Decimal, "12.5");
+            assert_eq!(e.to_string(), "numeric is decimal: 12.5 (diesel::sql_types::Numeric as u64)");
+        }
+
+        #[test]
+        fn retarget() {
+            // -1: one digit, weight 0, negative.
+            let e = super::from_sql::<ItemAmount, Numeric>(&[0, 1, 0, 0, 0x40, 0, 0, 0, 0, 1]).unwrap_err();
+            let e = e.downcast_ref::<ConversionError>().unwrap();
+            assert_eq!(e.rust_type, std::any::type_name::<ItemAmount>());
+            assert_eq!(e.kind, ConversionErrorKind::U64(NumericU64Error::Negative));
+            assert_eq!(e.value, "-1");
+        }
+
+        #[test]
+        fn invalid() {
+            let e = ConversionError::invalid::<PgU64, Numeric>(crate::error::LevelError::Negative, -3);
+            assert_eq!(e.kind, ConversionErrorKind::Invalid("numeric underflows".to_owned()));
+            assert_eq!(e.to_string(), "numeric underflows: -3 (diesel::sql_types::Numeric as diesel_pg_type_utils::PgU64)");
+        }
+    }
+
+    mod pg_u64_bigint {
+        use crate::PgU64Bigint;
+
+        const VALUES: [u64; 7] = [0, 1, 1 << 62, (1 << 63) - 1, 1 << 63, u64::MAX - 1, u64::MAX];
+
+        #[test]
+        fn round_trip() {
+            for value in VALUES {
+                assert_eq!(PgU64Bigint::decode(PgU64Bigint::encode(value)), value);
+            }
+        }
+
+        #[test]
+        fn preserves_order() {
+            for a in VALUES {
+                for b in VALUES {
+                    assert_eq!(a.cmp(&b), PgU64Bigint::encode(a).cmp(&PgU64Bigint::encode(b)));
+                }
+            }
+        }
+
+        #[test]
+        fn bounds() {
+            assert_eq!(PgU64Bigint::encode_bounds(0..=u64::MAX), (i64::MIN, i64::MAX));
+        }
+    }
+
     mod signed_u64 {
+        mod arithmetic {
+            use crate::SignedU64;
+
+            const fn signed(total: u64, is_negative: bool) -> SignedU64 {
+                SignedU64::new(total, is_negative)
+            }
+
+            #[test]
+            fn checked() {
+                assert_eq!(signed(1, false).checked_add(signed(4, true)), Some(signed(3, true)));
+                assert_eq!(signed(1, true).checked_sub(signed(4, true)), Some(signed(3, false)));
+                assert_eq!(signed(u64::MAX, true).checked_add(signed(1, false)), Some(signed(u64::MAX - 1, true)));
+                assert_eq!(signed(u64::MAX, true).checked_sub(signed(1, false)), None);
+                assert_eq!(signed(u64::MAX, false).checked_add(signed(1, false)), None);
+                assert_eq!(signed(u64::MAX, false).checked_mul(signed(1, true)), Some(signed(u64::MAX, true)));
+                assert_eq!(signed(u64::MAX, false).checked_mul(signed(2, true)), None);
+            }
+
+            #[test]
+            fn saturating() {
+                assert_eq!(signed(u64::MAX, true).saturating_sub(signed(1, false)), signed(u64::MAX, true));
+                assert_eq!(signed(u64::MAX, false).saturating_add(signed(1, false)), signed(u64::MAX, false));
+                assert_eq!(signed(u64::MAX, false).saturating_mul(signed(2, true)), signed(u64::MAX, true));
+                assert_eq!(signed(3, false).saturating_mul(signed(2, true)), signed(6, true));
+            }
+
+            #[test]
+            fn overflowing() {
+                assert_eq!(signed(u64::MAX, true).overflowing_sub(signed(2, false)), (signed(1, true), true));
+                assert_eq!(signed(u64::MAX, false).overflowing_add(signed(2, true)), (signed(u64::MAX - 2, false), false));
+                assert_eq!(signed(1 << 63, false).overflowing_mul(signed(2, false)), (signed(0, false), true));
+            }
+
+            #[test]
+            #[should_panic]
+            fn operator_overflow_panics() {
+                let _ = signed(u64::MAX, false) + signed(1, false);
+            }
+        }
+
+        mod canonical_zero {
+            use crate::{RawSignedU64, SignedU64};
+
+            #[test]
+            fn constructors() {
+                assert!(!SignedU64::new(0, true).is_negative());
+                assert!(!SignedU64::from(RawSignedU64 { total: 0, is_negative: true }).is_negative());
+                assert_eq!(
+                    RawSignedU64::from(SignedU64::new(0, true)),
+                    RawSignedU64 { total: 0, is_negative: false },
+                );
+            }
+
+            #[test]
+            fn arithmetic() {
+                let one = SignedU64::new(1, false);
+                assert!(!(-SignedU64::ZERO).is_negative());
+                assert!(!(-one + one).is_negative());
+                assert!(!(one - one).is_negative());
+                assert!(!(-one * SignedU64::ZERO).is_negative());
+            }
+        }
+
+        mod conversions {
+            use crate::{PgU64, SignedU64, error::SignedU64Error};
+
+            const fn signed(total: u64, is_negative: bool) -> SignedU64 {
+                SignedU64::new(total, is_negative)
+            }
+
+            #[test]
+            fn from_ints() {
+                assert_eq!(SignedU64::from(i64::MIN), signed(1 << 63, true));
+                assert_eq!(SignedU64::from(-1i64), signed(1, true));
+                assert_eq!(SignedU64::from(0i64), signed(0, false));
+                assert_eq!(SignedU64::from(PgU64::from(7)), signed(7, false));
+                assert_eq!(SignedU64::try_from(-i128::from(u64::MAX)), Ok(signed(u64::MAX, true)));
+                assert_eq!(SignedU64::try_from(i128::from(u64::MAX) + 1), Err(SignedU64Error::Overflow));
+            }
+
+            #[test]
+            fn into_i128() {
+                assert_eq!(i128::from(signed(u64::MAX, true)), -i128::from(u64::MAX));
+                assert_eq!(i128::from(signed(0, true)), 0);
+            }
+
+            #[test]
+            fn negative_zero_is_normalized() {
+                let zero = SignedU64::try_from(-0i128).unwrap();
+                assert!(!zero.is_negative());
+                let zero: SignedU64 = "-0".parse().unwrap();
+                assert!(!zero.is_negative());
+                assert_eq!(signed(0, true).to_string(), "0");
+            }
+
+            #[test]
+            fn string_round_trip() {
+                for value in [signed(123, true), signed(0, false), signed(u64::MAX, false), signed(u64::MAX, true)] {
+                    assert_eq!(value.to_string().parse::<SignedU64>(), Ok(value));
+                }
+                assert_eq!(signed(123, true).to_string(), "-123");
+                assert_eq!(format!("{:+}", signed(5, false)), "+5");
+                assert_eq!("18446744073709551616".parse::<SignedU64>(), Err(SignedU64Error::Overflow));
+                assert!(matches!("1.5".parse::<SignedU64>(), Err(SignedU64Error::Parse(_))));
+            }
+        }
+
         mod ord_and_eq {
             use crate::SignedU64;
 
             const fn test_data() -> [SignedU64; 6] {
-                [SignedU64 {
-                    total: 0,
-                    is_negative: false,
-                },
-                SignedU64 {
-                    total: 0,
-                    is_negative: true,
-                },
-                SignedU64 {
-                    total: 1,
-                    is_negative: false,
-                },
-                SignedU64 {
-                    total: 1,
-                    is_negative: true,
-                },
-                SignedU64 {
-                    total: 4,
-                    is_negative: false,
-                },
-                SignedU64 {
-                    total: 4,
-                    is_negative: true,
-                }]
+                [SignedU64::new(0, false),
+                SignedU64::new(0, true),
+                SignedU64::new(1, false),
+                SignedU64::new(1, true),
+                SignedU64::new(4, false),
+                SignedU64::new(4, true)]
             }
 
             macro_rules! test_single_sign {
@@ -0,0 +1,666 @@
+//! Direct decoding and encoding of Postgres' binary `numeric` wire format.
+//!
+//! A `numeric` is sent as a header of four 16-bit fields (`ndigits`, `weight`, `sign`, `dscale`)
+//! followed by `ndigits` base-10000 digits, most significant first. Digit `i` carries a factor of
+//! `10000^(weight - i)`. Going through this directly avoids materialising a `BigDecimal` per row.
+
+use std::{
+    fmt::{Debug, Display, Formatter},
+    io::Write,
+};
+
+use bigdecimal::{BigDecimal, RoundingMode, Signed, ToPrimitive};
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Numeric,
+};
+
+use crate::{
+    error::{
+        NumericU64Error,
+        NumericU128Error,
+    },
+    rounding::Rounding,
+};
+
+const NBASE: u64 = 10_000;
+
+const SIGN_POSITIVE: u16 = 0x0000;
+const SIGN_NEGATIVE: u16 = 0x4000;
+
+/// Borrowed view over a `numeric` in the binary wire format.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct NumericView<'a> {
+    weight: i16,
+    is_negative: bool,
+    digits: &'a [u8],
+}
+
+impl<'a> NumericView<'a> {
+    /// Returns `None` for anything that isn't a finite, well-formed numeric (`NaN`, infinities,
+    /// truncated input or out of range digits).
+    pub(crate) fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let (header, digits) = bytes.split_first_chunk::<8>()?;
+        let ndigits = u16::from_be_bytes([header[0], header[1]]);
+        let weight = i16::from_be_bytes([header[2], header[3]]);
+        let is_negative = match u16::from_be_bytes([header[4], header[5]]) {
+            SIGN_POSITIVE => false,
+            SIGN_NEGATIVE => true,
+            _ => return None,
+        };
+
+        if digits.len() != usize::from(ndigits) * 2 {
+            return None;
+        }
+        let view = Self {
+            weight,
+            is_negative,
+            digits,
+        };
+        if view.digits().any(|digit| u64::from(digit) >= NBASE) {
+            return None;
+        }
+
+        Some(view)
+    }
+
+    pub(crate) fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    pub(crate) fn digits(&self) -> impl Iterator<Item = u16> + 'a {
+        self.digits.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+    }
+
+    /// Absolute value of the numeric.
+    pub(crate) fn magnitude<T: Magnitude>(&self) -> Result<T, MagnitudeError> {
+        let mut total = T::ZERO;
+        let mut has_fraction = false;
+        let mut exponent = i32::from(self.weight);
+        for digit in self.digits() {
+            if exponent >= 0 {
+                total = total.push_digit(digit).ok_or(MagnitudeError::Overflow)?;
+            } else if digit != 0 {
+                has_fraction = true;
+            }
+            exponent -= 1;
+        }
+        // Postgres strips trailing zero digits, so they are implied by the weight.
+        while exponent >= 0 && total != T::ZERO {
+            total = total.push_digit(0).ok_or(MagnitudeError::Overflow)?;
+            exponent -= 1;
+        }
+
+        if has_fraction {
+            Err(MagnitudeError::Decimal)
+        } else {
+            Ok(total)
+        }
+    }
+}
+
+impl NumericView<'_> {
+    /// The base-10000 digit carrying a factor of `10000^exponent`.
+    fn digit_at(&self, exponent: i32) -> u16 {
+        let index = i32::from(self.weight) - exponent;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.digits.get(index * 2..index * 2 + 2))
+            .map_or(0, |pair| u16::from_be_bytes([pair[0], pair[1]]))
+    }
+
+    /// The exponent of the last digit that is actually sent.
+    fn last_exponent(&self) -> i32 {
+        i32::from(self.weight) + 1 - self.digits.len() as i32 / 2
+    }
+
+    /// Absolute value of the numeric, with any fractional part dropped.
+    fn integer_magnitude<T: Magnitude>(&self) -> Result<T, MagnitudeError> {
+        let mut total = T::ZERO;
+        for exponent in (0..=i32::from(self.weight)).rev() {
+            total = total.push_digit(self.digit_at(exponent)).ok_or(MagnitudeError::Overflow)?;
+        }
+        Ok(total)
+    }
+
+    /// Absolute value of the numeric, rounded to an integer. The rounding direction takes the sign
+    /// into account, so `-0.5` floors to a magnitude of `1`.
+    pub(crate) fn rounded_magnitude<T: Magnitude>(&self, rounding: Rounding) -> Result<T, MagnitudeError> {
+        let truncated = self.integer_magnitude::<T>()?;
+
+        let first = self.digit_at(-1);
+        let rest = (self.last_exponent()..-1).any(|exponent| self.digit_at(exponent) != 0);
+        let half = (NBASE / 2) as u16;
+        if first == 0 && !rest {
+            return Ok(truncated);
+        }
+        let round_away = match rounding {
+            Rounding::Truncate => false,
+            Rounding::Floor => self.is_negative,
+            Rounding::Ceil => !self.is_negative,
+            Rounding::HalfEven => first > half || (first == half && (rest || truncated.pop_digit().1 % 2 == 1)),
+        };
+
+        if round_away {
+            truncated.increment().ok_or(MagnitudeError::Overflow)
+        } else {
+            Ok(truncated)
+        }
+    }
+
+    /// Absolute value of the numeric multiplied by `10^scale`, failing with `Decimal` if that
+    /// still has a fractional part.
+    pub(crate) fn scaled_magnitude<T: Magnitude>(&self, scale: u32) -> Result<T, MagnitudeError> {
+        let mut total: T = self.integer_magnitude()?;
+        let mut position = 0;
+        for exponent in (self.last_exponent()..0).rev() {
+            let digit = self.digit_at(exponent);
+            for decimal in [digit / 1000, digit / 100 % 10, digit / 10 % 10, digit % 10] {
+                position += 1;
+                if position <= scale {
+                    total = total.push_decimal(decimal).ok_or(MagnitudeError::Overflow)?;
+                } else if decimal != 0 {
+                    return Err(MagnitudeError::Decimal);
+                }
+            }
+        }
+        while position < scale {
+            total = total.push_decimal(0).ok_or(MagnitudeError::Overflow)?;
+            position += 1;
+        }
+
+        Ok(total)
+    }
+}
+
+impl Display for NumericView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_negative && self.digits().any(|digit| digit != 0) {
+            f.write_str("-")?;
+        }
+        if self.weight < 0 {
+            f.write_str("0")?;
+        }
+        for exponent in (0..=i32::from(self.weight)).rev() {
+            if exponent == i32::from(self.weight) {
+                write!(f, "{}", self.digit_at(exponent))?;
+            } else {
+                write!(f, "{:04}", self.digit_at(exponent))?;
+            }
+        }
+
+        let fraction: String = (self.last_exponent()..0)
+            .rev()
+            .map(|exponent| format!("{:04}", self.digit_at(exponent)))
+            .collect();
+        let fraction = fraction.trim_end_matches('0');
+        if !fraction.is_empty() {
+            write!(f, ".{fraction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why the magnitude of a numeric doesn't fit into an unsigned integer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum MagnitudeError {
+    Overflow,
+    Decimal,
+}
+
+impl From<MagnitudeError> for NumericU64Error {
+    fn from(e: MagnitudeError) -> Self {
+        match e {
+            MagnitudeError::Overflow => Self::Overflow,
+            MagnitudeError::Decimal => Self::Decimal,
+        }
+    }
+}
+
+impl From<MagnitudeError> for NumericU128Error {
+    fn from(e: MagnitudeError) -> Self {
+        match e {
+            MagnitudeError::Overflow => Self::Overflow,
+            MagnitudeError::Decimal => Self::Decimal,
+        }
+    }
+}
+
+/// Unsigned integers that a numeric's magnitude can be accumulated into.
+pub(crate) trait Magnitude: Copy + Eq + Debug + Display {
+    const ZERO: Self;
+
+    /// `self * 10000 + digit`, if it fits.
+    fn push_digit(self, digit: u16) -> Option<Self>;
+
+    /// `self * 10 + decimal`, if it fits.
+    fn push_decimal(self, decimal: u16) -> Option<Self>;
+
+    /// `(self / 10000, self % 10000)`
+    fn pop_digit(self) -> (Self, u16);
+
+    /// `self + 1`, if it fits.
+    fn increment(self) -> Option<Self>;
+
+    /// Truncates, so callers should check for a fractional part themselves.
+    fn from_big_decimal(big: &BigDecimal) -> Option<Self>;
+}
+
+impl Magnitude for u64 {
+    const ZERO: Self = 0;
+
+    fn push_digit(self, digit: u16) -> Option<Self> {
+        self.checked_mul(NBASE as Self)?.checked_add(digit.into())
+    }
+
+    fn push_decimal(self, decimal: u16) -> Option<Self> {
+        self.checked_mul(10)?.checked_add(decimal.into())
+    }
+
+    fn increment(self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    fn pop_digit(self) -> (Self, u16) {
+        (self / NBASE as Self, (self % NBASE as Self) as u16)
+    }
+
+    fn from_big_decimal(big: &BigDecimal) -> Option<Self> {
+        big.to_u64()
+    }
+}
+
+impl Magnitude for u128 {
+    const ZERO: Self = 0;
+
+    fn push_digit(self, digit: u16) -> Option<Self> {
+        self.checked_mul(NBASE as Self)?.checked_add(digit.into())
+    }
+
+    fn push_decimal(self, decimal: u16) -> Option<Self> {
+        self.checked_mul(10)?.checked_add(decimal.into())
+    }
+
+    fn increment(self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    fn pop_digit(self) -> (Self, u16) {
+        (self / NBASE as Self, (self % NBASE as Self) as u16)
+    }
+
+    fn from_big_decimal(big: &BigDecimal) -> Option<Self> {
+        big.to_u128()
+    }
+}
+
+/// Writes an integer numeric in the binary wire format, in the same normalized form Postgres
+/// produces itself.
+pub(crate) fn write_integer<W: Write, T: Magnitude>(out: &mut W, total: T, is_negative: bool) -> std::io::Result<()> {
+    // Little endian, since that's the order they fall out of the division. u128::MAX has 39
+    // decimal digits, so it needs 10 base-10000 digits.
+    let mut groups = [0u16; 10];
+    let mut len = 0;
+    let mut rest = total;
+    while rest != T::ZERO {
+        let (quotient, remainder) = rest.pop_digit();
+        groups[len] = remainder;
+        rest = quotient;
+        len += 1;
+    }
+    let weight = (len as i16 - 1).max(0);
+    let trailing_zeroes = groups[..len].iter().take_while(|group| **group == 0).count();
+    let digits = &groups[trailing_zeroes..len];
+
+    let sign = if is_negative && total != T::ZERO {
+        SIGN_NEGATIVE
+    } else {
+        SIGN_POSITIVE
+    };
+    out.write_all(&(digits.len() as u16).to_be_bytes())?;
+    out.write_all(&weight.to_be_bytes())?;
+    out.write_all(&sign.to_be_bytes())?;
+    // dscale
+    out.write_all(&0u16.to_be_bytes())?;
+    for digit in digits.iter().rev() {
+        out.write_all(&digit.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+/// Writes `total / 10^scale` in the binary wire format, with a display scale of `scale`.
+pub(crate) fn write_scaled<W: Write>(out: &mut W, total: u64, is_negative: bool, scale: u16) -> std::io::Result<()> {
+    // Pad the fraction out to whole base-10000 digits, so that the low `fraction_groups` digits are
+    // exactly the fractional part. u64::MAX * 1000 needs 6 base-10000 digits.
+    let padding = (4 - scale % 4) % 4;
+    let fraction_groups = usize::from(scale.div_ceil(4));
+    let mut groups = [0u16; 6];
+    let mut len = 0;
+    let mut rest = u128::from(total) * 10u128.pow(padding.into());
+    while rest != 0 {
+        groups[len] = (rest % u128::from(NBASE)) as u16;
+        rest /= u128::from(NBASE);
+        len += 1;
+    }
+    let weight = if len == 0 { 0 } else { len as i16 - 1 - fraction_groups as i16 };
+    let trailing_zeroes = groups[..len].iter().take_while(|group| **group == 0).count();
+    let digits = &groups[trailing_zeroes..len];
+
+    let sign = if is_negative && total != 0 {
+        SIGN_NEGATIVE
+    } else {
+        SIGN_POSITIVE
+    };
+    out.write_all(&(digits.len() as u16).to_be_bytes())?;
+    out.write_all(&weight.to_be_bytes())?;
+    out.write_all(&sign.to_be_bytes())?;
+    out.write_all(&scale.to_be_bytes())?;
+    for digit in digits.iter().rev() {
+        out.write_all(&digit.to_be_bytes())?;
+    }
+    Ok(())
+}
+
+/// Sign and magnitude of a `numeric`, for types whose magnitude fits in `T`.
+///
+/// Decodes the wire format directly and only goes through `BigDecimal` when it can't.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NumericParts<T> {
+    pub total: Result<T, MagnitudeError>,
+    pub is_negative: bool,
+    /// The original value, kept for error messages when it doesn't fit in `total`.
+    rendered: Option<String>,
+}
+
+pub(crate) type NumericU64Parts = NumericParts<u64>;
+pub(crate) type NumericU128Parts = NumericParts<u128>;
+
+impl<T: Magnitude> NumericParts<T> {
+    pub(crate) fn new(total: T, is_negative: bool) -> Self {
+        Self {
+            total: Ok(total),
+            is_negative,
+            rendered: None,
+        }
+    }
+
+    fn decoded(total: Result<T, MagnitudeError>, is_negative: bool, render: impl FnOnce() -> String) -> Self {
+        Self {
+            rendered: total.is_err().then(render),
+            total,
+            is_negative,
+        }
+    }
+
+    /// Whether this is a negative number, as opposed to a negative zero.
+    pub(crate) fn is_strictly_negative(&self) -> bool {
+        self.is_negative && self.total != Ok(T::ZERO)
+    }
+
+    /// The decoded value, for error messages. Values decoded with a scale are described by their
+    /// mantissa.
+    pub(crate) fn describe(&self) -> String {
+        match (&self.rendered, self.total) {
+            (Some(rendered), _) => rendered.clone(),
+            (None, Ok(total)) if self.is_strictly_negative() => format!("-{total}"),
+            (None, Ok(total)) => total.to_string(),
+            (None, Err(e)) => format!("{e:?}"),
+        }
+    }
+
+    fn from_view(view: NumericView<'_>) -> Self {
+        Self::decoded(view.magnitude(), view.is_negative(), || view.to_string())
+    }
+
+    fn from_big_decimal(big: BigDecimal, original: &BigDecimal) -> Self {
+        let magnitude = big.abs();
+        let total = match T::from_big_decimal(&magnitude) {
+            None => Err(MagnitudeError::Overflow),
+            Some(_) if !magnitude.is_integer() => Err(MagnitudeError::Decimal),
+            Some(total) => Ok(total),
+        };
+        Self::decoded(total, big.is_negative(), || original.to_string())
+    }
+
+    /// Decodes the numeric multiplied by `10^scale`.
+    pub(crate) fn from_sql_scaled(bytes: PgValue<'_>, scale: u32) -> deserialize::Result<Self> {
+        if let Some(view) = NumericView::parse(bytes.as_bytes()) {
+            return Ok(Self::decoded(view.scaled_magnitude(scale), view.is_negative(), || view.to_string()));
+        }
+        let big = <BigDecimal as FromSql<Numeric, Pg>>::from_sql(bytes)?;
+        Ok(Self::from_big_decimal(&big * BigDecimal::new(1.into(), -i64::from(scale)), &big))
+    }
+
+    /// Decodes the numeric rounded to an integer.
+    pub(crate) fn from_sql_rounded(bytes: PgValue<'_>, rounding: Rounding) -> deserialize::Result<Self> {
+        if let Some(view) = NumericView::parse(bytes.as_bytes()) {
+            return Ok(Self::decoded(view.rounded_magnitude(rounding), view.is_negative(), || view.to_string()));
+        }
+        let big = <BigDecimal as FromSql<Numeric, Pg>>::from_sql(bytes)?;
+        let mode = match rounding {
+            Rounding::Truncate => RoundingMode::Down,
+            Rounding::HalfEven => RoundingMode::HalfEven,
+            Rounding::Ceil => RoundingMode::Ceiling,
+            Rounding::Floor => RoundingMode::Floor,
+        };
+        Ok(Self::from_big_decimal(big.with_scale_round(0, mode), &big))
+    }
+}
+
+impl<T: Magnitude> FromSql<Numeric, Pg> for NumericParts<T> {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        if let Some(view) = NumericView::parse(bytes.as_bytes()) {
+            return Ok(Self::from_view(view));
+        }
+        let big = <BigDecimal as FromSql<Numeric, Pg>>::from_sql(bytes)?;
+        Ok(Self::from_big_decimal(big.clone(), &big))
+    }
+}
+
+impl<T: Magnitude> ToSql<Numeric, Pg> for NumericParts<T> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        let total = self.total.map_err(|e| format!("cannot encode numeric: {e:?}"))?;
+        write_integer(out, total, self.is_negative)?;
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MagnitudeError, NumericU64Parts, NumericU128Parts, NumericView, write_integer, write_scaled, SIGN_NEGATIVE, SIGN_POSITIVE};
+
+    fn encode(weight: i16, sign: u16, digits: &[u16]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend((digits.len() as u16).to_be_bytes());
+        bytes.extend(weight.to_be_bytes());
+        bytes.extend(sign.to_be_bytes());
+        bytes.extend(0u16.to_be_bytes());
+        for digit in digits {
+            bytes.extend(digit.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> NumericU64Parts {
+        NumericU64Parts::from_view(NumericView::parse(bytes).unwrap())
+    }
+
+    fn decode_u128(bytes: &[u8]) -> NumericU128Parts {
+        NumericU128Parts::from_view(NumericView::parse(bytes).unwrap())
+    }
+
+    #[test]
+    fn round_trip() {
+        for value in [0, 1, 9_999, 10_000, 10_001, 100_000_000, 123_456_789, u64::MAX - 1, u64::MAX] {
+            for is_negative in [false, true] {
+                let mut bytes = vec![];
+                write_integer(&mut bytes, value, is_negative).unwrap();
+                let parts = decode(&bytes);
+                assert_eq!(parts.total, Ok(value));
+                assert_eq!(parts.is_negative, is_negative && value != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_u128() {
+        for value in [0, 1, u64::MAX as u128 + 1, 10u128.pow(38), u128::MAX - 1, u128::MAX] {
+            for is_negative in [false, true] {
+                let mut bytes = vec![];
+                write_integer(&mut bytes, value, is_negative).unwrap();
+                let parts = decode_u128(&bytes);
+                assert_eq!(parts.total, Ok(value));
+                assert_eq!(parts.is_negative, is_negative && value != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn normalized_encoding() {
+        let mut bytes = vec![];
+        write_integer(&mut bytes, 0u64, true).unwrap();
+        assert_eq!(bytes, encode(0, SIGN_POSITIVE, &[]));
+
+        let mut bytes = vec![];
+        write_integer(&mut bytes, 100_000_000u64, false).unwrap();
+        assert_eq!(bytes, encode(2, SIGN_POSITIVE, &[1]));
+
+        let mut bytes = vec![];
+        write_integer(&mut bytes, 12_345u64, true).unwrap();
+        assert_eq!(bytes, encode(1, SIGN_NEGATIVE, &[1, 2345]));
+    }
+
+    #[test]
+    fn classification() {
+        // 12.50
+        assert_eq!(decode(&encode(0, SIGN_POSITIVE, &[12, 5000])).total, Err(MagnitudeError::Decimal));
+        // 0.0001
+        assert_eq!(decode(&encode(-1, SIGN_POSITIVE, &[1])).total, Err(MagnitudeError::Decimal));
+        // 12.0000, with a zero fractional digit.
+        assert_eq!(decode(&encode(0, SIGN_POSITIVE, &[12, 0])).total, Ok(12));
+        // 10^20
+        assert_eq!(decode(&encode(5, SIGN_POSITIVE, &[1])).total, Err(MagnitudeError::Overflow));
+        // 10^20 + 0.5 overflows before it is a decimal.
+        assert_eq!(
+            decode(&encode(5, SIGN_POSITIVE, &[1, 0, 0, 0, 0, 0, 5000])).total,
+            Err(MagnitudeError::Overflow),
+        );
+        // u64::MAX + 1
+        assert_eq!(
+            decode(&encode(4, SIGN_POSITIVE, &[1844, 6744, 737, 955, 1616])).total,
+            Err(MagnitudeError::Overflow),
+        );
+        assert_eq!(
+            decode_u128(&encode(4, SIGN_POSITIVE, &[1844, 6744, 737, 955, 1616])).total,
+            Ok(u64::MAX as u128 + 1),
+        );
+        // 10^40
+        assert_eq!(decode_u128(&encode(10, SIGN_POSITIVE, &[1])).total, Err(MagnitudeError::Overflow));
+    }
+
+    #[test]
+    fn pg_u64_rejects_fractions() {
+        use crate::{error::{ConversionErrorKind, NumericU64Error}, unsigned_u64, PgU64};
+
+        // BigDecimal's to_u64 used to truncate this to 12.
+        let e = unsigned_u64::<PgU64>(decode(&encode(0, SIGN_POSITIVE, &[12, 5000]))).unwrap_err();
+        assert_eq!(e.kind, ConversionErrorKind::U64(NumericU64Error::Decimal));
+        assert_eq!(e.value, "12.5");
+    }
+
+    fn decode_scaled(bytes: &[u8], scale: u32) -> Result<u64, MagnitudeError> {
+        NumericView::parse(bytes).unwrap().scaled_magnitude(scale)
+    }
+
+    #[test]
+    fn scaled() {
+        // 12.50
+        assert_eq!(decode_scaled(&encode(0, SIGN_POSITIVE, &[12, 5000]), 2), Ok(1250));
+        assert_eq!(decode_scaled(&encode(0, SIGN_POSITIVE, &[12, 5000]), 1), Ok(125));
+        assert_eq!(decode_scaled(&encode(0, SIGN_POSITIVE, &[12, 5000]), 0), Err(MagnitudeError::Decimal));
+        // 0.00000001
+        assert_eq!(decode_scaled(&encode(-2, SIGN_POSITIVE, &[1]), 8), Ok(1));
+        assert_eq!(decode_scaled(&encode(-2, SIGN_POSITIVE, &[1]), 7), Err(MagnitudeError::Decimal));
+        // 10000
+        assert_eq!(decode_scaled(&encode(1, SIGN_POSITIVE, &[1]), 3), Ok(10_000_000));
+        // 10^19 with one decimal place doesn't fit.
+        assert_eq!(decode_scaled(&encode(4, SIGN_POSITIVE, &[1000]), 1), Err(MagnitudeError::Overflow));
+    }
+
+    #[test]
+    fn scaled_round_trip() {
+        for scale in [0, 1, 2, 4, 5, 8, 19] {
+            for value in [0, 1, 7, 1250, 10_000, 123_456_789, u64::MAX] {
+                let mut bytes = vec![];
+                write_scaled(&mut bytes, value, true, scale).unwrap();
+                let view = NumericView::parse(&bytes).unwrap();
+                assert_eq!(view.scaled_magnitude(scale.into()), Ok(value));
+                assert_eq!(view.is_negative(), value != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_encoding() {
+        let mut bytes = vec![];
+        write_scaled(&mut bytes, 1250, false, 2).unwrap();
+        let mut expected = encode(0, SIGN_POSITIVE, &[12, 5000]);
+        expected[6..8].copy_from_slice(&2u16.to_be_bytes());
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn rounded() {
+        use crate::rounding::Rounding::{self, *};
+
+        let cases: [(&[u16], i16, u16, [u64; 4]); 8] = [
+            // value, weight, sign, [truncate, half even, ceil, floor]
+            (&[12, 5000], 0, SIGN_POSITIVE, [12, 12, 13, 12]),
+            (&[13, 5000], 0, SIGN_POSITIVE, [13, 14, 14, 13]),
+            (&[12, 5000, 1], 0, SIGN_POSITIVE, [12, 13, 13, 12]),
+            (&[12, 4999], 0, SIGN_POSITIVE, [12, 12, 13, 12]),
+            (&[12], 0, SIGN_POSITIVE, [12, 12, 12, 12]),
+            (&[1], -2, SIGN_POSITIVE, [0, 0, 1, 0]),
+            (&[12, 5000], 0, SIGN_NEGATIVE, [12, 12, 12, 13]),
+            (&[1], -2, SIGN_NEGATIVE, [0, 0, 0, 1]),
+        ];
+        let roundings: [Rounding; 4] = [Truncate, HalfEven, Ceil, Floor];
+        for (digits, weight, sign, expected) in cases {
+            let bytes = encode(weight, sign, digits);
+            let view = NumericView::parse(&bytes).unwrap();
+            for (rounding, expected) in roundings.into_iter().zip(expected) {
+                assert_eq!(view.rounded_magnitude::<u64>(rounding), Ok(expected), "{digits:?} {rounding:?}");
+            }
+        }
+
+        // u64::MAX + 0.5
+        let bytes = encode(4, SIGN_POSITIVE, &[1844, 6744, 737, 955, 1615, 5000]);
+        let view = NumericView::parse(&bytes).unwrap();
+        assert_eq!(view.rounded_magnitude::<u64>(Truncate), Ok(u64::MAX));
+        assert_eq!(view.rounded_magnitude::<u64>(Ceil), Err(MagnitudeError::Overflow));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(NumericView::parse(&encode(0, SIGN_POSITIVE, &[12, 5000])).unwrap().to_string(), "12.5");
+        assert_eq!(NumericView::parse(&encode(-2, SIGN_NEGATIVE, &[1])).unwrap().to_string(), "-0.00000001");
+        assert_eq!(NumericView::parse(&encode(2, SIGN_POSITIVE, &[1, 20])).unwrap().to_string(), "100200000");
+        assert_eq!(NumericView::parse(&encode(0, SIGN_NEGATIVE, &[])).unwrap().to_string(), "0");
+        assert_eq!(
+            NumericView::parse(&encode(4, SIGN_POSITIVE, &[1844, 6744, 737, 955, 1616])).unwrap().to_string(),
+            "18446744073709551616",
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        // NaN
+        assert!(NumericView::parse(&encode(0, 0xC000, &[])).is_none());
+        // Digit out of range.
+        assert!(NumericView::parse(&encode(0, SIGN_POSITIVE, &[10_000])).is_none());
+        // Truncated.
+        assert!(NumericView::parse(&encode(1, SIGN_POSITIVE, &[1, 2])[..10]).is_none());
+    }
+}
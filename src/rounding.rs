@@ -0,0 +1,107 @@
+//! Opt-in rounding of `numeric` values into integers, for columns like `AVG(...)` where a
+//! fractional part is expected rather than an error.
+
+use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, Output, ToSql},
+    sql_types::Numeric,
+    AsExpression,
+    FromSqlRow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    numeric::NumericU64Parts,
+    PgU64,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Towards zero.
+    Truncate,
+    /// To the nearest integer, with ties going to the even one.
+    HalfEven,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards negative infinity.
+    Floor,
+}
+
+/// Type level [`Rounding`], for use as the parameter of [`PgU64Rounded`].
+///
+/// The supertraits let the wrapper derive everything its inner `u64` has.
+pub trait RoundingPolicy: Debug + Copy + Default + Ord + Hash + Send + Sync + 'static {
+    const ROUNDING: Rounding;
+}
+
+macro_rules! rounding_policy {
+    ($name:ident) => {
+        #[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        impl RoundingPolicy for $name {
+            const ROUNDING: Rounding = Rounding::$name;
+        }
+    };
+}
+
+rounding_policy!(Truncate);
+rounding_policy!(HalfEven);
+rounding_policy!(Ceil);
+rounding_policy!(Floor);
+
+/// A [`PgU64`] that rounds fractional values according to `P` instead of failing with
+/// [`NumericU64Error::Decimal`](crate::error::NumericU64Error::Decimal).
+///
+/// Values that are still negative after rounding fail the same way `PgU64` does, so `-0.4`
+/// decodes to `0` under [`HalfEven`] but is an error under [`Floor`].
+#[derive(Debug, Copy, Clone, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[serde(transparent, bound = "")]
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(sql_type = Numeric)]
+pub struct PgU64Rounded<P: RoundingPolicy>(u64, #[serde(skip)] PhantomData<P>);
+
+impl<P: RoundingPolicy> PgU64Rounded<P> {
+    pub fn assume_valid(u: u64) -> Self {
+        Self(u, PhantomData)
+    }
+
+    pub fn inner(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl<P: RoundingPolicy> From<u64> for PgU64Rounded<P> {
+    fn from(u: u64) -> Self {
+        Self(u, PhantomData)
+    }
+}
+
+impl<P: RoundingPolicy> From<PgU64Rounded<P>> for u64 {
+    fn from(r: PgU64Rounded<P>) -> Self {
+        r.0
+    }
+}
+
+impl<P: RoundingPolicy> From<PgU64Rounded<P>> for PgU64 {
+    fn from(r: PgU64Rounded<P>) -> Self {
+        r.0.into()
+    }
+}
+
+impl<P: RoundingPolicy> FromSql<Numeric, Pg> for PgU64Rounded<P> {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let parts = NumericU64Parts::from_sql_rounded(bytes, P::ROUNDING)?;
+        Ok(Self(crate::unsigned_u64::<Self>(parts)?, PhantomData))
+    }
+}
+
+impl<P: RoundingPolicy> ToSql<Numeric, Pg> for PgU64Rounded<P> {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <NumericU64Parts as ToSql<Numeric, Pg>>::to_sql(&NumericU64Parts::new(self.0, false), &mut out.reborrow())
+    }
+}
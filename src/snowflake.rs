@@ -0,0 +1,510 @@
+//! Discord style snowflakes, which start with the milliseconds since an epoch, so that IDs sort by
+//! creation time and a time range maps to a range of IDs.
+
+use std::{
+    marker::PhantomData,
+    ops::RangeInclusive,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use diesel::{
+    dsl::{Between, GtEq},
+    expression::AsExpression,
+    sql_types::SqlType,
+    Expression,
+    ExpressionMethods,
+};
+
+use crate::{error::SnowflakeError, PgU64, PgU64Bigint};
+
+/// The start of 2015, in milliseconds since the Unix epoch.
+pub const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// An ID whose top bits are the milliseconds since its [`Snowflake::LAYOUT`]'s epoch it was
+/// created at, the top 42 bits since 2015 by default.
+///
+/// Implemented by [`PgU64`], [`PgU64Bigint`], everything made with
+/// [`wrap_snowflake!`](crate::wrap::wrap_snowflake), and wrappers with `with(snowflake)`. Since
+/// both storages keep the order of the `u64`, comparing a column with these bounds works the same
+/// way as comparing the IDs in Rust.
+pub trait Snowflake: Sized {
+    /// How the IDs are split, for both the helpers here and [`SnowflakeGenerator`]. Wrappers pick
+    /// theirs with `with(snowflake = ...)`.
+    const LAYOUT: SnowflakeLayout = SnowflakeLayout::DISCORD;
+
+    /// The largest snowflake that keeps its order once stored, `i64::MAX` for IDs held as `i64`s.
+    /// The time helpers clamp to it, and generators stop before it.
+    const MAX_SNOWFLAKE: u64 = u64::MAX;
+
+    fn from_snowflake(snowflake: u64) -> Self;
+
+    fn snowflake(&self) -> u64;
+
+    fn created_at(&self) -> SystemTime {
+        Self::LAYOUT.decode(self.snowflake()).created_at
+    }
+
+    /// The smallest ID created at `time`. Times before the epoch give `0`.
+    fn min_for_time(time: SystemTime) -> Self {
+        Self::from_snowflake(timestamp_bits::<Self>(time))
+    }
+
+    /// The largest ID created at `time`, within the same millisecond. Times past the last
+    /// representable millisecond give [`Snowflake::MAX_SNOWFLAKE`].
+    fn max_for_time(time: SystemTime) -> Self {
+        let max = timestamp_bits::<Self>(time) | mask(Self::LAYOUT.timestamp_shift());
+        Self::from_snowflake(max.min(Self::MAX_SNOWFLAKE))
+    }
+
+    /// The IDs created between both times, inclusive.
+    fn range_for_times(times: RangeInclusive<SystemTime>) -> RangeInclusive<Self> {
+        let (start, end) = times.into_inner();
+        Self::min_for_time(start)..=Self::max_for_time(end)
+    }
+
+    /// `column BETWEEN ...` with the bounds of [`Snowflake::range_for_times`].
+    fn created_between<C>(column: C, times: RangeInclusive<SystemTime>) -> Between<C, Self, Self>
+    where
+        C: Expression + ExpressionMethods,
+        C::SqlType: SqlType,
+        Self: AsExpression<C::SqlType>,
+    {
+        let (start, end) = Self::range_for_times(times).into_inner();
+        column.between(start, end)
+    }
+
+    /// `column >= ...` for the IDs created at `time` or later, as in "the last 24 hours".
+    fn created_since<C>(column: C, time: SystemTime) -> GtEq<C, Self>
+    where
+        C: Expression + ExpressionMethods,
+        C::SqlType: SqlType,
+        Self: AsExpression<C::SqlType>,
+    {
+        column.ge(Self::min_for_time(time))
+    }
+}
+
+/// The integers a snowflake can be wrapped as with `with(snowflake)`. `i64`s hold the same bits,
+/// which stay positive as long as the timestamp leaves the top bit alone.
+pub trait SnowflakeRepr: Copy {
+    /// The largest snowflake that doesn't change sign, used as [`Snowflake::MAX_SNOWFLAKE`].
+    const MAX_SNOWFLAKE: u64;
+
+    fn from_snowflake(snowflake: u64) -> Self;
+
+    fn snowflake(self) -> u64;
+}
+
+impl SnowflakeRepr for u64 {
+    const MAX_SNOWFLAKE: u64 = u64::MAX;
+
+    fn from_snowflake(snowflake: u64) -> Self {
+        snowflake
+    }
+
+    fn snowflake(self) -> u64 {
+        self
+    }
+}
+
+impl SnowflakeRepr for i64 {
+    const MAX_SNOWFLAKE: u64 = i64::MAX as u64;
+
+    fn from_snowflake(snowflake: u64) -> Self {
+        snowflake as i64
+    }
+
+    fn snowflake(self) -> u64 {
+        self as u64
+    }
+}
+
+/// The milliseconds since the epoch, shifted into place. Saturates at both ends, so times before
+/// the epoch get the first ID and times past the last representable millisecond get the last one.
+fn timestamp_bits<S: Snowflake>(time: SystemTime) -> u64 {
+    let since_epoch = millis_since(S::LAYOUT.epoch_ms, time);
+    since_epoch.min(max_millis::<S>()) << S::LAYOUT.timestamp_shift()
+}
+
+/// The last millisecond `S` can hold, within both its layout and [`Snowflake::MAX_SNOWFLAKE`].
+fn max_millis<S: Snowflake>() -> u64 {
+    S::MAX_SNOWFLAKE >> S::LAYOUT.timestamp_shift()
+}
+
+fn millis_since(epoch_ms: u64, time: SystemTime) -> u64 {
+    let since_unix = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let millis = u64::try_from(since_unix.as_millis()).unwrap_or(u64::MAX);
+    millis.saturating_sub(epoch_ms)
+}
+
+/// How a [`Snowflake`] is split: from the top, the milliseconds since `epoch_ms`, then
+/// `worker_bits` of worker ID and `sequence_bits` of sequence within the millisecond.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SnowflakeLayout {
+    pub epoch_ms: u64,
+    pub worker_bits: u32,
+    pub sequence_bits: u32,
+}
+
+impl SnowflakeLayout {
+    /// Discord's layout, where the worker bits hold both the worker and process IDs.
+    pub const DISCORD: Self = Self {
+        epoch_ms: DISCORD_EPOCH_MS,
+        worker_bits: 10,
+        sequence_bits: 12,
+    };
+
+    pub const fn timestamp_shift(&self) -> u32 {
+        self.worker_bits + self.sequence_bits
+    }
+
+    /// The last millisecond the timestamp bits can hold.
+    pub const fn max_millis(&self) -> u64 {
+        u64::MAX >> self.timestamp_shift()
+    }
+
+    pub fn decode(&self, snowflake: u64) -> SnowflakeParts {
+        let millis = snowflake >> self.timestamp_shift();
+        SnowflakeParts {
+            created_at: UNIX_EPOCH + Duration::from_millis(self.epoch_ms + millis),
+            worker_id: (snowflake >> self.sequence_bits) & mask(self.worker_bits),
+            sequence: snowflake & mask(self.sequence_bits),
+        }
+    }
+}
+
+impl Default for SnowflakeLayout {
+    fn default() -> Self {
+        Self::DISCORD
+    }
+}
+
+const fn mask(bits: u32) -> u64 {
+    (1 << bits) - 1
+}
+
+/// What a snowflake is made of, from [`SnowflakeLayout::decode`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SnowflakeParts {
+    pub created_at: SystemTime,
+    pub worker_id: u64,
+    pub sequence: u64,
+}
+
+/// Makes `S`s in the application instead of with a `BIGSERIAL`, with `S`'s
+/// [`LAYOUT`](Snowflake::LAYOUT). That includes `wrap_i64!` and `wrap_u64!` types declared
+/// `with(snowflake)`.
+///
+/// IDs only ever increase, across threads too, and IDs from workers with different IDs never
+/// collide. When a millisecond runs out of sequence numbers, or the clock goes backwards, the
+/// generator carries on from the last ID it made, so timestamps can run slightly ahead of the clock.
+/// Once the timestamp no longer fits in the layout, generating fails instead of wrapping around.
+#[derive(Debug)]
+pub struct SnowflakeGenerator<S> {
+    worker_id: u64,
+    /// The timestamp and sequence of the last ID, packed together so they update atomically.
+    last: AtomicU64,
+    _snowflake: PhantomData<fn() -> S>,
+}
+
+impl<S: Snowflake> SnowflakeGenerator<S> {
+    /// Fails if the layout's timestamp bits can't hold the current time, or if `worker_id` doesn't
+    /// fit in its worker bits.
+    pub fn new(worker_id: u64) -> Result<Self, SnowflakeError> {
+        Self::new_at(worker_id, SystemTime::now())
+    }
+
+    fn new_at(worker_id: u64, now: SystemTime) -> Result<Self, SnowflakeError> {
+        let layout = S::LAYOUT;
+        if layout.timestamp_shift() >= u64::BITS || millis_since(layout.epoch_ms, now) > max_millis::<S>() {
+            return Err(SnowflakeError::TooManyBits {
+                worker_bits: layout.worker_bits,
+                sequence_bits: layout.sequence_bits,
+            });
+        }
+        if worker_id > mask(layout.worker_bits) {
+            return Err(SnowflakeError::WorkerId {
+                worker_id,
+                worker_bits: layout.worker_bits,
+            });
+        }
+        Ok(Self {
+            worker_id,
+            last: AtomicU64::new(0),
+            _snowflake: PhantomData,
+        })
+    }
+
+    pub fn layout(&self) -> SnowflakeLayout {
+        S::LAYOUT
+    }
+
+    pub fn worker_id(&self) -> u64 {
+        self.worker_id
+    }
+
+    pub fn generate(&self) -> Result<S, SnowflakeError> {
+        self.next_at(SystemTime::now()).map(S::from_snowflake)
+    }
+
+    fn next_at(&self, now: SystemTime) -> Result<u64, SnowflakeError> {
+        let layout = S::LAYOUT;
+        let sequence_bits = layout.sequence_bits;
+        let max_millis = max_millis::<S>();
+        let out_of_time = SnowflakeError::OutOfTime {
+            timestamp_bits: max_millis.count_ones(),
+        };
+        let millis = millis_since(layout.epoch_ms, now);
+        if millis > max_millis {
+            return Err(out_of_time);
+        }
+        let earliest = millis << sequence_bits;
+        let mut last = self.last.load(Ordering::Relaxed);
+        let next = loop {
+            let next = match last.checked_add(1) {
+                Some(next) if next >> sequence_bits <= max_millis => earliest.max(next),
+                _ => return Err(out_of_time),
+            };
+            match self.last.compare_exchange_weak(last, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => break next,
+                Err(actual) => last = actual,
+            }
+        };
+        let millis = next >> sequence_bits;
+        let sequence = next & mask(sequence_bits);
+        Ok((millis << layout.timestamp_shift()) | (self.worker_id << sequence_bits) | sequence)
+    }
+}
+
+impl Snowflake for PgU64 {
+    fn from_snowflake(snowflake: u64) -> Self {
+        Self(snowflake)
+    }
+
+    fn snowflake(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Snowflake for PgU64Bigint {
+    fn from_snowflake(snowflake: u64) -> Self {
+        Self(snowflake)
+    }
+
+    fn snowflake(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashSet,
+        thread,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use diesel::{debug_query, pg::Pg, prelude::*};
+
+    use super::{Snowflake, SnowflakeGenerator, SnowflakeLayout, SnowflakeParts, DISCORD_EPOCH_MS};
+    use crate::{error::SnowflakeError, wrap, PgU64, PgU64Bigint};
+
+    wrap::wrap_u64_bigint!(MessageKey<Pg> with(snowflake));
+    wrap::wrap_i64!(JourneyId<Pg> with(snowflake));
+
+    /// Milliseconds since 2023-11-14, with room for 256 workers.
+    const TICKET_LAYOUT: SnowflakeLayout = SnowflakeLayout { epoch_ms: 1_700_000_000_000, worker_bits: 8, sequence_bits: 14 };
+
+    wrap::wrap_i64!(TicketId<Pg> with(snowflake = TICKET_LAYOUT));
+
+    diesel::table! {
+        messages (id) {
+            id -> BigInt,
+        }
+    }
+
+    diesel::table! {
+        journeys (id) {
+            id -> BigInt,
+        }
+    }
+
+    #[test]
+    fn snowflake() {
+        // The example from Discord's documentation.
+        let id = PgU64::from(175928847299117063);
+        let created_at = UNIX_EPOCH + Duration::from_millis(1462015105796);
+        assert_eq!(id.created_at(), created_at);
+        assert!(PgU64::min_for_time(created_at) <= id);
+        assert!(id <= PgU64::max_for_time(created_at));
+        assert_eq!(PgU64::max_for_time(created_at).created_at(), created_at);
+        assert_eq!(PgU64::min_for_time(UNIX_EPOCH), PgU64::from(0));
+        assert_eq!(PgU64::min_for_time(UNIX_EPOCH + Duration::from_millis(DISCORD_EPOCH_MS + 1)), PgU64::from(1 << 22));
+
+        let day = Duration::from_secs(24 * 60 * 60);
+        let query = messages::table
+            .filter(MessageKey::created_between(messages::id, created_at - day..=created_at))
+            .filter(MessageKey::created_since(messages::id, created_at))
+            .select(messages::id);
+        let range = MessageKey::range_for_times(created_at - day..=created_at);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            format!(
+                r#"SELECT "messages"."id" FROM "messages" WHERE (("messages"."id" BETWEEN $1 AND $2) AND ("messages"."id" >= $3)) -- binds: [{:?}, {:?}, {:?}]"#,
+                range.start(), range.end(), MessageKey::min_for_time(created_at),
+            ),
+        );
+    }
+
+    /// Few enough bits to run out of sequence numbers in a test.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Tiny(u64);
+
+    impl Snowflake for Tiny {
+        const LAYOUT: SnowflakeLayout = SnowflakeLayout { epoch_ms: DISCORD_EPOCH_MS, worker_bits: 5, sequence_bits: 2 };
+
+        fn from_snowflake(snowflake: u64) -> Self {
+            Self(snowflake)
+        }
+
+        fn snowflake(&self) -> u64 {
+            self.0
+        }
+    }
+
+    /// Leaves 24 bits of timestamp, a bit over 4.5 hours.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Wide(u64);
+
+    impl Snowflake for Wide {
+        const LAYOUT: SnowflakeLayout = SnowflakeLayout { epoch_ms: DISCORD_EPOCH_MS, worker_bits: 20, sequence_bits: 20 };
+
+        fn from_snowflake(snowflake: u64) -> Self {
+            Self(snowflake)
+        }
+
+        fn snowflake(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn far_future() {
+        use diesel::sql_types::BigInt;
+
+        let far_future = UNIX_EPOCH + Duration::from_secs(1 << 50);
+        // The `i64` would be -1 without the clamp, and sort before every other ID.
+        assert_eq!(*JourneyId::max_for_time(far_future).inner(), i64::MAX);
+        assert_eq!(JourneyId::min_for_time(far_future).snowflake() >> 22, (i64::MAX as u64) >> 22);
+        assert_eq!(crate::test::to_sql::<BigInt, _>(JourneyId::max_for_time(far_future)), i64::MAX.to_be_bytes());
+        // `PgU64Bigint` keeps the order of the whole `u64`, so it doesn't need clamping.
+        assert_eq!(PgU64Bigint::max_for_time(far_future).snowflake(), u64::MAX);
+        assert_eq!(crate::test::to_sql::<BigInt, _>(PgU64Bigint::max_for_time(far_future)), i64::MAX.to_be_bytes());
+
+        let now = UNIX_EPOCH + Duration::from_millis(1462015105796);
+        let query = journeys::table
+            .filter(JourneyId::created_between(journeys::id, now..=far_future))
+            .select(journeys::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            format!(
+                r#"SELECT "journeys"."id" FROM "journeys" WHERE ("journeys"."id" BETWEEN $1 AND $2) -- binds: [{:?}, JourneyId({})]"#,
+                JourneyId::min_for_time(now), i64::MAX,
+            ),
+        );
+    }
+
+    #[test]
+    fn generator() {
+        assert_eq!(
+            SnowflakeGenerator::<Tiny>::new(32).unwrap_err(),
+            SnowflakeError::WorkerId { worker_id: 32, worker_bits: 5 },
+        );
+
+        let generator = SnowflakeGenerator::<Tiny>::new(3).unwrap();
+        let now = UNIX_EPOCH + Duration::from_millis(DISCORD_EPOCH_MS + 1000);
+        let ids: Vec<_> = (0..5).map(|_| Tiny(generator.next_at(now).unwrap())).collect();
+        // The fifth ID runs out of sequence numbers and borrows the next millisecond.
+        assert_eq!(
+            ids.iter().map(|id| Tiny::LAYOUT.decode(id.0)).collect::<Vec<_>>(),
+            [(1000, 0), (1000, 1), (1000, 2), (1000, 3), (1001, 0)].map(|(millis, sequence)| SnowflakeParts {
+                created_at: UNIX_EPOCH + Duration::from_millis(DISCORD_EPOCH_MS + millis),
+                worker_id: 3,
+                sequence,
+            }),
+        );
+        assert_eq!(ids[4].created_at(), UNIX_EPOCH + Duration::from_millis(DISCORD_EPOCH_MS + 1001));
+        assert!(Tiny::min_for_time(ids[4].created_at()) <= ids[4] && ids[4] <= Tiny::max_for_time(ids[4].created_at()));
+        // Going back in time still increases.
+        assert!(generator.next_at(now - Duration::from_secs(1)).unwrap() > ids[4].0);
+
+        let generator = SnowflakeGenerator::<JourneyId>::new(1).unwrap();
+        let journey = generator.generate().unwrap();
+        assert_eq!(SnowflakeLayout::DISCORD.decode(journey.snowflake()).worker_id, 1);
+        assert!(journey.created_at() <= std::time::SystemTime::now() + Duration::from_secs(1));
+
+        let ids: Vec<Vec<JourneyId>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (0..1000).map(|_| generator.generate().unwrap()).collect()))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        assert!(ids.iter().all(|ids| ids.is_sorted()));
+        assert_eq!(ids.iter().flatten().collect::<HashSet<_>>().len(), 4000);
+    }
+
+    #[test]
+    fn generator_layout() {
+        let generator = SnowflakeGenerator::<TicketId>::new(200).unwrap();
+        assert_eq!(generator.layout(), TICKET_LAYOUT);
+
+        let at = UNIX_EPOCH + Duration::from_millis(TICKET_LAYOUT.epoch_ms + 5000);
+        let ticket = TicketId::from_snowflake(generator.next_at(at).unwrap());
+        assert_eq!(*ticket.inner(), (5000 << 22) | (200 << 14));
+        assert_eq!(ticket.created_at(), at);
+        assert_eq!(TICKET_LAYOUT.decode(ticket.snowflake()), SnowflakeParts { created_at: at, worker_id: 200, sequence: 0 });
+        assert_eq!(TicketId::min_for_time(at), TicketId::assume_valid(5000 << 22));
+
+        let ticket = generator.generate().unwrap();
+        assert_eq!(TICKET_LAYOUT.decode(ticket.snowflake()).worker_id, 200);
+        assert!(ticket.created_at() <= std::time::SystemTime::now() + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn generator_out_of_time() {
+        // 24 bits of timestamp ran out a few hours into 2015.
+        assert_eq!(
+            SnowflakeGenerator::<Wide>::new(0).unwrap_err(),
+            SnowflakeError::TooManyBits { worker_bits: 20, sequence_bits: 20 },
+        );
+
+        let last = UNIX_EPOCH + Duration::from_millis(DISCORD_EPOCH_MS + Wide::LAYOUT.max_millis());
+        let generator = SnowflakeGenerator::<Wide>::new_at(0, last).unwrap();
+        let id = Wide(generator.next_at(last).unwrap());
+        assert_eq!(id.created_at(), last);
+        assert_eq!(Wide::max_for_time(last + Duration::from_secs(1)), Wide(u64::MAX));
+        assert_eq!(
+            generator.next_at(last + Duration::from_millis(1)).unwrap_err(),
+            SnowflakeError::OutOfTime { timestamp_bits: 24 },
+        );
+
+        // IDs held as `i64`s run out a bit earlier, before the sign bit.
+        let sign_bit = UNIX_EPOCH + Duration::from_millis(TICKET_LAYOUT.epoch_ms + (1 << 41));
+        assert_eq!(
+            SnowflakeGenerator::<TicketId>::new_at(0, sign_bit).unwrap_err(),
+            SnowflakeError::TooManyBits { worker_bits: 8, sequence_bits: 14 },
+        );
+        let generator = SnowflakeGenerator::<TicketId>::new_at(0, sign_bit - Duration::from_millis(1)).unwrap();
+        assert!(generator.next_at(sign_bit - Duration::from_millis(1)).unwrap() <= i64::MAX as u64);
+        assert_eq!(generator.next_at(sign_bit).unwrap_err(), SnowflakeError::OutOfTime { timestamp_bits: 41 });
+
+        // The sequence numbers of the last millisecond run out too.
+        let generator = SnowflakeGenerator::<Tiny>::new_at(0, UNIX_EPOCH).unwrap();
+        generator.last.store(u64::MAX >> Tiny::LAYOUT.worker_bits, std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(
+            generator.next_at(UNIX_EPOCH).unwrap_err(),
+            SnowflakeError::OutOfTime { timestamp_bits: 57 },
+        );
+    }
+}
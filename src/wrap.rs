@@ -12,7 +12,7 @@ macro_rules! impl_sql_convert {
                     $intermediate
                     as
                     $crate::diesel::deserialize::FromSql<$sql, $db>
-                >::from_sql(bytes)?;
+                >::from_sql(bytes).map_err($crate::error::ConversionError::retarget::<$final>)?;
                 let $forward = intermediate;
                 let converted = $convert_forward;
                 Ok(converted)
@@ -33,29 +33,318 @@ macro_rules! impl_sql_convert {
     }
 }
 
+/// Wraps `$wrapped` in a newtype that is stored as `$sql`, decoding through `$intermediate`.
+///
+/// The name can be preceded by a visibility, which defaults to `pub` (use `pub(self)` for a
+/// private type). The conversions can be followed by, in this order:
+///
+/// - `field <vis>` to give the tuple field a visibility. It is private by default, so the only
+///   ways in are the generated constructors.
+/// - `with(...)` to opt into more items: `inner_mut`, `into_inner`, `from` (`From<$wrapped>`),
+///   `deref` (`Deref<Target = $wrapped>`) and `arith`. `arith` is for integer quantities, and adds
+///   `Add`/`Sub` between wrappers, `Mul` by a `$wrapped` scalar, `Sum`, and the `checked_*` and
+///   `saturating_*` versions of each. Like [`SignedU64`](crate::SignedU64) the operators panic on
+///   overflow. `inner_mut`, `from` and `arith` skip validation the same way `assume_valid` does,
+///   and `from` can't be combined with `validate` since both provide `TryFrom<$wrapped>`.
+///   `snowflake` implements [`Snowflake`](crate::snowflake::Snowflake) for wrapped `u64`s and
+///   `i64`s, so that a [`SnowflakeGenerator`](crate::snowflake::SnowflakeGenerator) can make them.
+///   It uses Discord's layout unless given another, as in `snowflake = MY_LAYOUT`.
+/// - `validate |value| -> Error { ... }` to enforce an invariant on the wrapped value. The block
+///   gets a reference to the value and returns `Result<(), Error>`, and runs when decoding from the
+///   database, in the generated `new` and `TryFrom`, and when deserializing with serde. Failures
+///   while decoding come back as a [`ConversionError`](crate::error::ConversionError) holding the
+///   value and the validator's message.
+///
+/// ```ignore
+/// wrap_type! {
+///     #[derive(Debug, Copy, Clone)]
+///     pub(crate) Xp<Pg>(BigInt > i64)
+///     field pub(crate)
+///     with(inner_mut, deref)
+///     validate |xp| -> XpError { if *xp < 0 { Err(XpError::Negative) } else { Ok(()) } }
+/// }
+/// ```
 #[macro_export]
 macro_rules! wrap_type {
     {
         $(#[derive($($trait:ident),+)])*
-        $name:ident <$db: ty> ( $sql:ty > $intermediate:ty > $wrapped:ty )
+        $name:ident <$db:ty> $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @vis [pub]
+            $(#[derive($($trait),+)])*
+            $name<$db> $($rest)*
+        }
+    };
+    {
+        $(#[derive($($trait:ident),+)])*
+        $vis:vis $name:ident <$db:ty> $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @vis [$vis]
+            $(#[derive($($trait),+)])*
+            $name<$db> $($rest)*
+        }
+    };
+    // The `wrap_*!` macros pass their conversions through here, so the visibility only needs to be
+    // parsed once.
+    {
+        @spec [$($spec:tt)*]
+        $(#[derive($($trait:ident),+)])*
+        $name:ident <$db:ty> $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @vis [pub]
+            $(#[derive($($trait),+)])*
+            $name<$db> $($spec)* $($rest)*
+        }
+    };
+    {
+        @spec [$($spec:tt)*]
+        $(#[derive($($trait:ident),+)])*
+        $vis:vis $name:ident <$db:ty> $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @vis [$vis]
+            $(#[derive($($trait),+)])*
+            $name<$db> $($spec)* $($rest)*
+        }
+    };
+    {
+        @vis [$($vis:tt)*]
+        $(#[derive($($trait:ident),+)])*
+        $name:ident <$db:ty> ( $sql:ty > $intermediate:ty > $wrapped:ty )
             |$forward:ident| $convert_forward:block
             |$backward:ident| $convert_backward:block
+            $(field $field_vis:vis)?
+            $(with($($with:ident $(= $with_arg:expr)?),* $(,)?))?
+            $(validate |$valid:ident| -> $err:ty $validate:block)?
     } => {
-        $(#[derive($($trait),+)])*
+        $crate::wrap::wrap_type! {
+            @struct [$(#[derive($($trait),+)])*]
+            [$($vis)*] $name($($field_vis)? $wrapped) $sql $(, $err)?
+        }
+
+        $crate::wrap::wrap_type! {
+            @impl $name<$db>($sql > $intermediate > $wrapped)
+                |$forward| $convert_forward
+                |$backward| $convert_backward
+                $(validate $err)?
+        }
+
+        $($($crate::wrap::wrap_type!(@with $with $name $wrapped $(= $with_arg)?);)*)?
+
+        $($crate::wrap::wrap_type!(@validate $name $wrapped |$valid| -> $err $validate);)?
+    };
+    (
+        @vis [$($vis:tt)*]
+        $(#[derive($($trait:ident),+)])*
+        $name:ident < $db:ty > ( $sql:ty > $wrapped:ty )
+        $($rest:tt)*
+    ) => {
+        $crate::wrap::wrap_type! {
+            @vis [$($vis)*]
+            $(#[derive($($trait),+)])*
+            $name<$db>($sql > $wrapped > $wrapped) |junk| { junk } |junk| { &junk }
+            $($rest)*
+        }
+    };
+    (@struct [$($attr:tt)*] [$($vis:tt)*] $name:ident ($field_vis:vis $wrapped:ty) $sql:ty) => {
+        $($attr)*
         #[derive($crate::serde::Serialize, $crate::serde::Deserialize)]
         #[derive($crate::diesel::AsExpression, $crate::diesel::FromSqlRow)]
         #[diesel(sql_type = $sql)]
-        pub struct $name($wrapped);
+        $($vis)* struct $name($field_vis $wrapped);
+    };
+    (@struct [$($attr:tt)*] [$($vis:tt)*] $name:ident ($field_vis:vis $wrapped:ty) $sql:ty, $err:ty) => {
+        $($attr)*
+        #[derive($crate::serde::Serialize)]
+        #[derive($crate::diesel::AsExpression, $crate::diesel::FromSqlRow)]
+        #[diesel(sql_type = $sql)]
+        $($vis)* struct $name($field_vis $wrapped);
+    };
+    (@with inner_mut $name:ident $wrapped:ty) => {
+        impl $name {
+            pub fn inner_mut(&mut self) -> &mut $wrapped {
+                &mut self.0
+            }
+        }
+    };
+    (@with into_inner $name:ident $wrapped:ty) => {
+        impl $name {
+            pub fn into_inner(self) -> $wrapped {
+                self.0
+            }
+        }
+    };
+    (@with from $name:ident $wrapped:ty) => {
+        impl From<$wrapped> for $name {
+            fn from(f: $wrapped) -> Self {
+                Self(f)
+            }
+        }
+    };
+    (@with deref $name:ident $wrapped:ty) => {
+        impl ::core::ops::Deref for $name {
+            type Target = $wrapped;
+
+            fn deref(&self) -> &$wrapped {
+                &self.0
+            }
+        }
+    };
+    (@with snowflake $name:ident $wrapped:ty $(= $layout:expr)?) => {
+        $crate::wrap::wrap_type! {
+            @snowflake [$name $($layout)?]
+            max <$wrapped as $crate::snowflake::SnowflakeRepr>::MAX_SNOWFLAKE,
+            |snowflake| {
+                <$wrapped as $crate::snowflake::SnowflakeRepr>::from_snowflake(snowflake)
+            }
+            |id| {
+                $crate::snowflake::SnowflakeRepr::snowflake(id)
+            }
+        }
+    };
+    (@snowflake [$name:ident $($layout:expr)?] $(max $max:expr,)? |$snowflake:ident| $from:block |$id:ident| $into:block) => {
+        impl $crate::snowflake::Snowflake for $name {
+            $(const LAYOUT: $crate::snowflake::SnowflakeLayout = $layout;)?
+            $(const MAX_SNOWFLAKE: u64 = $max;)?
+
+            fn from_snowflake($snowflake: u64) -> Self {
+                Self($from)
+            }
+
+            fn snowflake(&self) -> u64 {
+                let $id = self.0;
+                $into
+            }
+        }
+    };
+    (@with arith $name:ident $wrapped:ty) => {
+        impl $name {
+            pub fn checked_add(self, other: Self) -> Option<Self> {
+                self.0.checked_add(other.0).map(Self)
+            }
 
+            pub fn checked_sub(self, other: Self) -> Option<Self> {
+                self.0.checked_sub(other.0).map(Self)
+            }
+
+            pub fn checked_mul(self, factor: $wrapped) -> Option<Self> {
+                self.0.checked_mul(factor).map(Self)
+            }
+
+            pub fn saturating_add(self, other: Self) -> Self {
+                Self(self.0.saturating_add(other.0))
+            }
+
+            pub fn saturating_sub(self, other: Self) -> Self {
+                Self(self.0.saturating_sub(other.0))
+            }
+
+            pub fn saturating_mul(self, factor: $wrapped) -> Self {
+                Self(self.0.saturating_mul(factor))
+            }
+        }
+
+        impl ::core::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                self.checked_add(other).expect(concat!(stringify!($name), " addition overflowed"))
+            }
+        }
+
+        impl ::core::ops::AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl ::core::ops::Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                self.checked_sub(other).expect(concat!(stringify!($name), " subtraction overflowed"))
+            }
+        }
+
+        impl ::core::ops::SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl ::core::ops::Mul<$wrapped> for $name {
+            type Output = Self;
+
+            fn mul(self, factor: $wrapped) -> Self {
+                self.checked_mul(factor).expect(concat!(stringify!($name), " multiplication overflowed"))
+            }
+        }
+
+        impl ::core::ops::MulAssign<$wrapped> for $name {
+            fn mul_assign(&mut self, factor: $wrapped) {
+                *self = *self * factor;
+            }
+        }
+
+        impl ::core::iter::Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self(0), ::core::ops::Add::add)
+            }
+        }
+
+        impl<'a> ::core::iter::Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.copied().sum()
+            }
+        }
+    };
+    (@validate $name:ident $wrapped:ty |$valid:ident| -> $err:ty $validate:block) => {
+        impl $name {
+            pub fn validate($valid: &$wrapped) -> Result<(), $err> $validate
+
+            pub fn new(f: $wrapped) -> Result<Self, $err> {
+                Self::validate(&f)?;
+                Ok(Self(f))
+            }
+        }
+
+        impl TryFrom<$wrapped> for $name {
+            type Error = $err;
+
+            fn try_from(f: $wrapped) -> Result<Self, Self::Error> {
+                Self::new(f)
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let f = <$wrapped as $crate::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                Self::new(f).map_err(<D::Error as $crate::serde::de::Error>::custom)
+            }
+        }
+    };
+    {
+        @impl $name:ident <$db: ty> ( $sql:ty > $intermediate:ty > $wrapped:ty )
+            |$forward:ident| $convert_forward:block
+            |$backward:ident| $convert_backward:block
+            $(validate $err:ty)?
+    } => {
         impl $crate::diesel::deserialize::FromSql<$sql, $db> for $name {
             fn from_sql(bytes: <$db as $crate::diesel::backend::Backend>::RawValue<'_>) -> $crate::diesel::deserialize::Result<Self> {
                 let intermediate = <
                     $intermediate
                     as
                     $crate::diesel::deserialize::FromSql<$sql, $db>
-                >::from_sql(bytes)?;
+                >::from_sql(bytes).map_err($crate::error::ConversionError::retarget::<$name>)?;
                 let $forward = intermediate;
                 let converted = $convert_forward;
+                $(
+                    let validated: Result<(), $err> = $name::validate(&converted);
+                    validated.map_err(|e| $crate::error::ConversionError::invalid::<$name, $sql>(e, &converted))?;
+                )?
                 Ok($name(converted))
             }
         }
@@ -87,27 +376,17 @@ macro_rules! wrap_type {
             }
         }
     };
-    (
-        $(#[derive($($trait:ident),+)])*
-        $name:ident < $db:ty > ( $sql:ty > $wrapped:ty )
-    ) => {
-        $crate::wrap::wrap_type! {
-            $(#[derive($($trait),+)])*
-            $name<$db>($sql > $wrapped > $wrapped) |junk| { junk } |junk| { &junk }
-        }
-    };
 }
 
 #[macro_export]
 macro_rules! wrap_i32 {
     {
-        $(#[derive($($trait:ident),+)])*
-        $name:ident<$db:ty>
+        $($rest:tt)*
     } => {
         $crate::wrap::wrap_type! {
-            $(#[derive($($trait),+)])*
+            @spec [($crate::diesel::sql_types::Integer > i32)]
             #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-            $name<$db>($crate::diesel::sql_types::Integer > i32)
+            $($rest)*
         }
     };
 }
@@ -115,71 +394,566 @@ macro_rules! wrap_i32 {
 #[macro_export]
 macro_rules! wrap_i64 {
     {
-        $(#[derive($($trait:ident),+)])*
-        $name:ident<$db:ty>
+        $($rest:tt)*
     } => {
         $crate::wrap::wrap_type! {
-            $(#[derive(($trait),+)])*
+            @spec [($crate::diesel::sql_types::BigInt > i64)]
             #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-            $name<$db>($crate::diesel::sql_types::BigInt > i64)
+            $($rest)*
         }
     };
 }
 
 #[macro_export]
 macro_rules! wrap_u32 {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::BigInt > $crate::PgU32 > u32)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU32::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! wrap_u16 {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::Integer > $crate::PgU16 > u16)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU16::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! wrap_u8 {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::SmallInt > $crate::PgU8 > u8)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU8::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+/// Pass `rounding = Policy` (one of the types in [`rounding`](crate::rounding)) to round
+/// fractional values instead of rejecting them. Any other options then follow a comma.
+#[macro_export]
+macro_rules! wrap_u64 {
     {
         $(#[derive($($trait:ident),+)])*
-        $name:ident<$db:ty>
+        $name:ident<$db:ty>, rounding = $policy:ty $(, $($rest:tt)*)?
     } => {
         $crate::wrap::wrap_type! {
+            @vis [pub]
             $(#[derive($($trait),+)])*
             #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-            $name<$db>($crate::diesel::sql_types::BigInt > $crate::PgU32 > u32)
+            $name<$db>($crate::diesel::sql_types::Numeric > $crate::PgU64Rounded<$policy> > u64)
             |u| {
                 u.into()
             }
             |u| {
-                &$crate::PgU32::from(u)
+                &$crate::PgU64Rounded::<$policy>::from(u)
             }
+            $($($rest)*)?
         }
     };
-}
-
-#[macro_export]
-macro_rules! wrap_u64 {
     {
         $(#[derive($($trait:ident),+)])*
-        $name:ident<$db:ty>
+        $vis:vis $name:ident<$db:ty>, rounding = $policy:ty $(, $($rest:tt)*)?
     } => {
         $crate::wrap::wrap_type! {
+            @vis [$vis]
             $(#[derive($($trait),+)])*
             #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-            $name<$db>($crate::diesel::sql_types::Numeric > $crate::PgU64 > u64)
+            $name<$db>($crate::diesel::sql_types::Numeric > $crate::PgU64Rounded<$policy> > u64)
             |u| {
                 u.into()
             }
             |u| {
-                &$crate::PgU64::from(u)
+                &$crate::PgU64Rounded::<$policy>::from(u)
+            }
+            $($($rest)*)?
+        }
+    };
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::Numeric > $crate::PgU64 > u64)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU64::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+/// Like `wrap_u64!`, but stored in a `BIGINT` through [`PgU64Bigint`](crate::PgU64Bigint), which
+/// keeps the column 8 bytes wide and sorted the same way as the `u64`.
+#[macro_export]
+macro_rules! wrap_u64_bigint {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::BigInt > $crate::PgU64Bigint > u64)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU64Bigint::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! wrap_u128 {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::Numeric > $crate::PgU128 > u128)
+                |u| {
+                    u.into()
+                }
+                |u| {
+                    &$crate::PgU128::from(u)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! wrap_i128 {
+    {
+        $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::Numeric > $crate::PgI128 > i128)
+                |i| {
+                    i.into()
+                }
+                |i| {
+                    &$crate::PgI128::from(i)
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+    };
+}
+
+/// Wraps an ID that is a newtype over a `u64` snowflake, like serenity's `UserId`, which only
+/// needs `From<u64>` and `From<$wrapped> for u64`. The storage comes before the wrapped type:
+/// `numeric` goes through [`PgU64`](crate::PgU64), and `bigint` through
+/// [`PgU64Bigint`](crate::PgU64Bigint). The usual `wrap_type!` options can follow, and the
+/// wrapper implements [`Snowflake`](crate::snowflake::Snowflake).
+///
+/// ```ignore
+/// wrap_snowflake!(pub DiscordEmojiId<Pg>(bigint > serenity::model::id::EmojiId) with(from));
+/// ```
+#[macro_export]
+macro_rules! wrap_snowflake {
+    {
+        $(#[derive($($trait:ident),+)])*
+        $name:ident <$db:ty> ($storage:ident > $wrapped:ty) $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_snowflake! {
+            @$storage [$name $wrapped]
+            $(#[derive($($trait),+)])*
+            $name<$db> $($rest)*
+        }
+    };
+    {
+        $(#[derive($($trait:ident),+)])*
+        $vis:vis $name:ident <$db:ty> ($storage:ident > $wrapped:ty) $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_snowflake! {
+            @$storage [$name $wrapped]
+            $(#[derive($($trait),+)])*
+            $vis $name<$db> $($rest)*
+        }
+    };
+    {
+        @numeric [$name:ident $wrapped:ty] $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::Numeric > $crate::PgU64 > $wrapped)
+                |u| {
+                    <$wrapped>::from(u64::from(u))
+                }
+                |id| {
+                    &$crate::PgU64::from(u64::from(id))
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+
+        $crate::wrap::wrap_type! {
+            @snowflake [$name]
+            |snowflake| {
+                <$wrapped>::from(snowflake)
+            }
+            |id| {
+                u64::from(id)
+            }
+        }
+    };
+    {
+        @bigint [$name:ident $wrapped:ty] $($rest:tt)*
+    } => {
+        $crate::wrap::wrap_type! {
+            @spec [($crate::diesel::sql_types::BigInt > $crate::PgU64Bigint > $wrapped)
+                |u| {
+                    <$wrapped>::from(u64::from(u))
+                }
+                |id| {
+                    &$crate::PgU64Bigint::from(u64::from(id))
+                }
+            ]
+            #[derive(Debug, Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+            $($rest)*
+        }
+
+        $crate::wrap::wrap_type! {
+            @snowflake [$name]
+            |snowflake| {
+                <$wrapped>::from(snowflake)
+            }
+            |id| {
+                u64::from(id)
             }
         }
     };
 }
 
+/// Declares that every `$sub` is also a valid `$super`, for wrapped IDs like a card's asset ID
+/// that is always an asset ID, while the reverse only holds for some assets.
+///
+/// ```ignore
+/// id_subtype! {
+///     CardId: AssetId,
+///     check |asset| { asset.inner() >= &CARD_ASSET_START },
+///     query cards::table => cards::asset_id,
+/// }
+/// ```
+///
+/// This generates `From<$sub> for $super`. Both wrapped types need to be the same.
+///
+/// - `check` gives the closure-like block that decides whether a `$super` is a `$sub`, and adds
+///   `TryFrom<$super>`, failing with a [`DowncastError`](crate::error::DowncastError).
+/// - `query` names the subtype's table and its column holding the supertype ID. It adds an async
+///   `downcast` that looks for the ID in that column, and `eq_super` to compare the column with a
+///   supertype column or value, as in `.inner_join(assets::table.on(CardId::eq_super(assets::id)))`.
+#[macro_export]
+macro_rules! id_subtype {
+    {
+        $sub:ident : $super:ty
+        $(, check |$checked:ident| $check:block)?
+        $(, query $table:path => $column:path)?
+        $(,)?
+    } => {
+        impl From<$sub> for $super {
+            fn from(id: $sub) -> Self {
+                <$super>::assume_valid(id.into())
+            }
+        }
+
+        $(
+            impl TryFrom<$super> for $sub {
+                type Error = $crate::error::DowncastError;
+
+                fn try_from($checked: $super) -> Result<Self, Self::Error> {
+                    if $check {
+                        Ok(Self::assume_valid($checked.into()))
+                    } else {
+                        Err($crate::error::DowncastError::new::<Self>(format!("{:?}", $checked)))
+                    }
+                }
+            }
+        )?
+
+        $(
+            impl $sub {
+                /// The supertype ID as this subtype, if it's in the subtype's table.
+                pub async fn downcast<C: $crate::PgC>(conn: &mut C, id: $super) -> $crate::diesel::QueryResult<Option<Self>> {
+                    use $crate::diesel::{ExpressionMethods, QueryDsl};
+
+                    let query = $crate::diesel::select($crate::diesel::dsl::exists($table.filter($column.eq(id))));
+                    let found: bool = $crate::diesel_async::RunQueryDsl::get_result(query, conn).await?;
+                    Ok(found.then(|| Self::assume_valid(id.into())))
+                }
+
+                pub fn eq_super<T>(other: T) -> $crate::diesel::dsl::Eq<$column, T>
+                where
+                    T: $crate::diesel::expression::AsExpression<$crate::diesel::dsl::SqlTypeOf<$column>>,
+                {
+                    $crate::diesel::ExpressionMethods::eq($column, other)
+                }
+            }
+        )?
+    };
+}
+
 pub use impl_sql_convert;
 pub use wrap_type;
 pub use wrap_i32;
 pub use wrap_i64;
 pub use wrap_u64;
+pub use wrap_u64_bigint;
 pub use wrap_u32;
+pub use wrap_u16;
+pub use wrap_u8;
+pub use wrap_u128;
+pub use wrap_i128;
+pub use wrap_snowflake;
+pub use id_subtype;
 
 #[cfg(test)]
 mod test {
     use crate::wrap;
-    use diesel::pg::Pg;
+    use diesel::{pg::Pg, sql_types::{Integer, Numeric}, AsExpression, FromSqlRow};
 
     // Test each of the specialized impls.
     wrap::wrap_i32!(OldId<Pg>);
     wrap::wrap_u64!(AssetId<Pg>);
+    wrap::wrap_u64!(AverageAmount<Pg>, rounding = crate::rounding::HalfEven);
+    wrap::wrap_u64_bigint!(SnowflakeId<Pg>);
     wrap::wrap_i64!(NewId<Pg>);
+    wrap::wrap_u16!(SlotCapacity<Pg>);
+    wrap::wrap_u8!(Level<Pg>);
+    wrap::wrap_u128!(Balance<Pg>);
+    wrap::wrap_i128!(Delta<Pg>);
+    wrap::wrap_i32!(pub(crate) Points<Pg> field pub(crate) with(inner_mut, into_inner, from, deref));
+    wrap::wrap_u64!(Amount<Pg> with(arith));
+    wrap::wrap_u64!(pub(super) Average<Pg>, rounding = crate::rounding::Floor, with(into_inner));
+
+    wrap::wrap_i32! {
+        Rank<Pg>
+        validate |rank| -> crate::error::LevelError {
+            if *rank < 0 {
+                return Err(crate::error::LevelError::Negative);
+            }
+            Ok(())
+        }
+    }
+
+    // The conversions run inside the impls, so they can refer to `Self`.
+    wrap::wrap_type! {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+        Percent<Pg>(Integer > i32 > u8)
+        |i| { Self::clamp(i) }
+        |p| { i32::from(p) }
+    }
+
+    impl Percent {
+        fn clamp(i: i32) -> u8 {
+            i.clamp(0, 100) as u8
+        }
+    }
+
+    /// Doc comments and other attributes are allowed on derived wrappers.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[derive(AsExpression, FromSqlRow, crate::PgWrap)]
+    #[diesel(sql_type = Numeric)]
+    #[pg_wrap(sql = Numeric, via = crate::PgU64)]
+    pub struct DerivedAssetId(u64);
+
+    fn non_negative(i: &i32) -> Result<i32, crate::error::LevelError> {
+        if *i < 0 {
+            Err(crate::error::LevelError::Negative)
+        } else {
+            Ok(*i)
+        }
+    }
+
+    fn copy_level(i: &i32) -> i32 {
+        *i
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[derive(AsExpression, FromSqlRow, crate::PgWrap)]
+    #[diesel(sql_type = Integer)]
+    #[pg_wrap(sql = Integer, from = non_negative, to = copy_level)]
+    pub struct DerivedLevel(i32);
+
+    #[test]
+    fn derived_items() {
+        assert_eq!(*DerivedAssetId::assume_valid(4).inner(), 4);
+        assert_eq!(i32::from(DerivedLevel::assume_valid(3)), 3);
+    }
+
+    diesel::table! {
+        players (id) {
+            id -> Numeric,
+            level -> Integer,
+        }
+    }
+
+    #[test]
+    fn derived_sql() {
+        use diesel::{debug_query, prelude::*};
+
+        use crate::{error::{ConversionError, ConversionErrorKind}, test::{from_sql, to_sql}};
+
+        let query = players::table
+            .filter(players::id.eq(DerivedAssetId::assume_valid(4)))
+            .filter(players::level.eq(DerivedLevel::assume_valid(3)))
+            .select(players::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "players"."id" FROM "players" WHERE (("players"."id" = $1) AND ("players"."level" = $2)) -- binds: [DerivedAssetId(4), DerivedLevel(3)]"#,
+        );
+
+        let level = to_sql::<Integer, _>(DerivedLevel::assume_valid(3));
+        assert_eq!(level, 3i32.to_be_bytes());
+        assert_eq!(from_sql::<DerivedLevel, Integer>(&level).unwrap(), DerivedLevel::assume_valid(3));
+        let asset_id = to_sql::<Numeric, _>(DerivedAssetId::assume_valid(4));
+        assert_eq!(from_sql::<DerivedAssetId, Numeric>(&asset_id).unwrap(), DerivedAssetId::assume_valid(4));
+
+        let e = from_sql::<DerivedLevel, Integer>(&(-2i32).to_be_bytes()).unwrap_err();
+        let e = e.downcast_ref::<ConversionError>().unwrap();
+        assert_eq!(e.rust_type, std::any::type_name::<DerivedLevel>());
+        assert_eq!(e.kind, ConversionErrorKind::Invalid(crate::error::LevelError::Negative.to_string()));
+        assert_eq!(e.value, "-2");
+    }
+
+    #[test]
+    fn access() {
+        let mut points = Points::from(3);
+        *points.inner_mut() += 1;
+        points.0 += 1;
+        assert_eq!(*points, 5);
+        assert_eq!(points.inner(), Points::assume_valid(5).inner());
+        assert_eq!(points.into_inner(), 5);
+        assert_eq!(*Average::assume_valid(2).inner(), 2);
+        assert_eq!(Average::assume_valid(2).into_inner(), 2);
+    }
+
+    #[test]
+    fn arith() {
+        let mut amount = Amount::assume_valid(3);
+        amount += Amount::assume_valid(2);
+        assert_eq!(amount - Amount::assume_valid(1), Amount::assume_valid(4));
+        assert_eq!(amount * 2, Amount::assume_valid(10));
+        assert_eq!(Amount::assume_valid(1).checked_sub(amount), None);
+        assert_eq!(Amount::assume_valid(1).saturating_sub(amount), Amount::assume_valid(0));
+        assert_eq!(Amount::assume_valid(u64::MAX).checked_mul(2), None);
+        assert_eq!([amount, amount].iter().sum::<Amount>(), Amount::assume_valid(10));
+    }
+
+    diesel::table! {
+        cards (id) {
+            id -> Integer,
+            asset_id -> Numeric,
+        }
+    }
+
+    diesel::table! {
+        assets (id) {
+            id -> Numeric,
+        }
+    }
+
+    diesel::allow_tables_to_appear_in_same_query!(cards, assets);
+
+    wrap::wrap_u64!(CardAssetId<Pg>);
+    wrap::id_subtype! {
+        CardAssetId: AssetId,
+        check |asset| { *asset.inner() >= 100 },
+        query cards::table => cards::asset_id,
+    }
+
+    #[test]
+    fn id_subtype() {
+        use diesel::{debug_query, prelude::*};
+
+        assert_eq!(AssetId::from(CardAssetId::assume_valid(120)), AssetId::assume_valid(120));
+        assert_eq!(CardAssetId::try_from(AssetId::assume_valid(120)), Ok(CardAssetId::assume_valid(120)));
+        assert!(CardAssetId::try_from(AssetId::assume_valid(7)).is_err());
+
+        let query = cards::table.inner_join(assets::table.on(CardAssetId::eq_super(assets::id))).select(cards::id);
+        assert_eq!(
+            debug_query::<Pg, _>(&query).to_string(),
+            r#"SELECT "cards"."id" FROM ("cards" INNER JOIN "assets" ON ("cards"."asset_id" = "assets"."id")) -- binds: []"#,
+        );
+    }
+
+    #[test]
+    fn converted() {
+        let percent: Percent = crate::test::from_sql::<_, Integer>(&150i32.to_be_bytes()).unwrap();
+        assert_eq!(percent, Percent::assume_valid(100));
+    }
+
+    #[test]
+    fn validated() {
+        assert_eq!(Rank::new(3).map(i32::from), Ok(3));
+        assert!(Rank::new(-1).is_err());
+        assert!(Rank::try_from(-1).is_err());
+        assert_eq!(*Rank::assume_valid(-1).inner(), -1);
+    }
+
+    #[test]
+    fn validated_deserialize() {
+        use serde::{de::{value::Error, IntoDeserializer}, Deserialize};
+
+        let rank = Rank::deserialize(IntoDeserializer::<Error>::into_deserializer(3i32));
+        assert_eq!(rank.map(i32::from), Ok(3));
+        let rank = Rank::deserialize(IntoDeserializer::<Error>::into_deserializer(-1i32));
+        assert_eq!(rank.unwrap_err().to_string(), crate::error::LevelError::Negative.to_string());
+    }
+
+    #[test]
+    fn validated_from_sql() {
+        use crate::{error::{ConversionError, ConversionErrorKind}, test::from_sql};
+
+        assert_eq!(from_sql::<Rank, Integer>(&3i32.to_be_bytes()).map(i32::from).unwrap(), 3);
+        let e = from_sql::<Rank, Integer>(&(-1i32).to_be_bytes()).unwrap_err();
+        let e = e.downcast_ref::<ConversionError>().unwrap();
+        assert_eq!(e.kind, ConversionErrorKind::Invalid(crate::error::LevelError::Negative.to_string()));
+        assert_eq!(e.rust_type, std::any::type_name::<Rank>());
+        assert_eq!(e.value, "-1");
+    }
 }